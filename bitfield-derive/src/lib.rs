@@ -0,0 +1,165 @@
+//! Derive macros for [`mycelium-bitfield`].
+//!
+//! This crate should not be depended on directly. Instead, enable the
+//! `derive` feature of [`mycelium-bitfield`], which re-exports the macros in
+//! this crate.
+//!
+//! [`mycelium-bitfield`]: https://crates.io/crates/mycelium-bitfield
+#![warn(missing_docs, rustdoc::broken_intra_doc_links)]
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{
+    parse_macro_input, spanned::Spanned, Data, DeriveInput, Error, Fields, Ident, Meta, NestedMeta,
+};
+
+/// Derives an implementation of `FromBits` for a fieldless enum.
+///
+/// The enum must have a `#[repr(...)]` attribute with a primitive integer
+/// type. The generated implementation is generic over every bit
+/// representation that the `repr` type can be packed into.
+///
+/// - `BITS` is the smallest number of bits that can represent every
+///   variant's discriminant. For an enum with the default discriminants,
+///   this is determined by the number of variants; otherwise, it is
+///   determined by the largest discriminant, so enums whose discriminants
+///   are not contiguous are supported.
+/// - `try_from_bits` returns the variant whose discriminant is equal to the
+///   provided bits, or an `UnknownVariant` error if no variant matches. Bit
+///   patterns between sparse discriminants are rejected.
+/// - `into_bits` casts the variant to its discriminant.
+///
+/// See the documentation for the `FromBits` trait in `mycelium-bitfield` for
+/// examples.
+#[proc_macro_derive(FromBits)]
+pub fn derive_from_bits(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_bits(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_from_bits(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let data = match input.data {
+        Data::Enum(ref data) => data,
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "`FromBits` can only be derived for enums",
+            ))
+        }
+    };
+
+    if data.variants.is_empty() {
+        return Err(Error::new(
+            name.span(),
+            "`FromBits` cannot be derived for enums with no variants",
+        ));
+    }
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "`FromBits` cannot be derived for generic enums",
+        ));
+    }
+
+    let mut variants = Vec::with_capacity(data.variants.len());
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new(
+                variant.fields.span(),
+                "`FromBits` can only be derived for enums without fields",
+            ));
+        }
+        variants.push(&variant.ident);
+    }
+
+    let (repr, unsigned) = find_repr(&input)?;
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl<B> ::mycelium_bitfield::FromBits<B> for #name
+        where
+            #repr: ::mycelium_bitfield::FromBits<B, Error = ::core::convert::Infallible>,
+        {
+            const BITS: u32 = {
+                // the highest set bit of all the discriminants determines how
+                // many bits are needed to represent every variant.
+                let set_bits = 0 #( | (Self::#variants as #repr as #unsigned) )*;
+                let bits = <#unsigned>::BITS - set_bits.leading_zeros();
+                if bits == 0 {
+                    1
+                } else {
+                    bits
+                }
+            };
+            type Error = ::mycelium_bitfield::UnknownVariant;
+
+            #[allow(non_upper_case_globals, unreachable_patterns)]
+            fn try_from_bits(bits: B) -> ::core::result::Result<Self, Self::Error> {
+                let bits = match <#repr as ::mycelium_bitfield::FromBits<B>>::try_from_bits(bits) {
+                    ::core::result::Result::Ok(bits) => bits,
+                    ::core::result::Result::Err(infallible) => match infallible {},
+                };
+                // Declare a constant for each variant's discriminant, so that
+                // they may be used as patterns.
+                #(
+                    const #variants: #repr = #name::#variants as #repr;
+                )*
+                match bits {
+                    #( #variants => ::core::result::Result::Ok(Self::#variants), )*
+                    _ => ::core::result::Result::Err(::mycelium_bitfield::UnknownVariant::new(
+                        ::core::stringify!(#name),
+                        bits as #unsigned as u128,
+                    )),
+                }
+            }
+
+            fn into_bits(self) -> B {
+                <#repr as ::mycelium_bitfield::FromBits<B>>::into_bits(self as #repr)
+            }
+        }
+    })
+}
+
+/// Returns the integer type in the enum's `#[repr(...)]` attribute, and the
+/// unsigned integer type of the same width.
+fn find_repr(input: &DeriveInput) -> syn::Result<(Ident, Ident)> {
+    for attr in &input.attrs {
+        if !attr.path.is_ident("repr") {
+            continue;
+        }
+
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            _ => continue,
+        };
+
+        for nested in &list.nested {
+            let ident = match nested {
+                NestedMeta::Meta(Meta::Path(path)) => match path.get_ident() {
+                    Some(ident) => ident,
+                    None => continue,
+                },
+                _ => continue,
+            };
+            let unsigned = match ident.to_string().as_str() {
+                "u8" | "i8" => "u8",
+                "u16" | "i16" => "u16",
+                "u32" | "i32" => "u32",
+                "u64" | "i64" => "u64",
+                "u128" | "i128" => "u128",
+                "usize" | "isize" => "usize",
+                _ => continue,
+            };
+            return Ok((ident.clone(), Ident::new(unsigned, ident.span())));
+        }
+    }
+
+    Err(Error::new(
+        input.ident.span(),
+        "`FromBits` can only be derived for enums with a primitive integer \
+        representation (e.g. `#[repr(u8)]`)",
+    ))
+}
@@ -12,17 +12,48 @@
 ///
 /// The `bitfield!` macro generates a type with the following functions, where
 /// `T` is the integer type that represents the bitfield (one of `u8`, `u16`,
-/// `u32`, `u64`, or `usize`):
+/// `u32`, `u64`, `u128`, or `usize`, or one of the corresponding signed
+/// integer types; see [below](#signed-bitfields)):
 ///
 /// | Function | Description |
 /// |:--|:--|
-/// | `fn new() -> Self` | Returns a new instance of the bitfield type with all bits zeroed. |
+/// | `fn new() -> Self` | Returns a new instance of the bitfield type with all bits zeroed, except for any fields declared with [`must_be`](#required-values). |
 /// | `fn from_bits(bits: T) -> Self` | Converts a `T` into an instance of the bitfield type. |
+/// | `fn from_bits_truncate(bits: T) -> Self` | Converts a `T` into an instance of the bitfield type, clearing any bits which are not part of a declared field. |
+/// | `fn bits(&self) -> T` | Returns the raw bits of this bitfield as a `T`. This is the inverse of `from_bits`. |
 /// | `fn with<U>(self, packer: Self::Packer<U>, value: U) -> Self` | Given one of this type's generated packing specs for a `U`-typed value, and a `U`-typed value, returns a new instance of `Self` with the bit representation of `value` packed into the range represented by `packer`. |
+/// | `fn with_saturating<U>(self, packer: Self::Packer<U>, value: U) -> Self` | Like `with`, but if the bit representation of `value` does not fit in the range represented by `packer`, the field's maximum value (all ones) is packed instead. |
+/// | `const fn with_const(self, packer: Self::Packer<T>, value: T) -> Self` | Like `with`, but is a `const fn`, so that bitfields may be constructed at compile time. This only accepts packing specs for raw integer fields. |
+/// | `fn checked_with<U>(self, packer: Self::Packer<U>, value: U) -> Result<Self, OutOfRange>` | Like `with`, but returns an [`OutOfRange`] error rather than panicking if the bit representation of `value` does not fit in the range represented by `packer`. |
 /// | `fn set<U>(&mut self, packer: Self::Packer<U>, value: U) -> &mut Self` | Similar to `with`, except `self` is mutated in place, rather than returning a new  instance of `Self`. |
+/// | `fn replace<U>(&mut self, packer: Self::Packer<U>, value: U) -> U` | Like `set`, but returns the value previously stored in `packer`'s range, similar to [`Cell::replace`]. This panics if the previous value is not a valid bit pattern for a `U`. |
+/// | `fn wrapping_increment(&mut self, packer: Self::Packer<T>) -> &mut Self` | Adds 1 to the value of the raw integer field `packer`, wrapping around to 0 if the field is at its maximum value. |
+/// | `fn checked_increment(&mut self, packer: Self::Packer<T>) -> Option<&mut Self>` | Adds 1 to the value of the raw integer field `packer`, returning `None` (and leaving `self` unchanged) if the field is at its maximum value. |
+/// | `fn wrapping_decrement(&mut self, packer: Self::Packer<T>) -> &mut Self` | Subtracts 1 from the value of the raw integer field `packer`, wrapping around to the field's maximum value if the field is 0. |
+/// | `fn checked_decrement(&mut self, packer: Self::Packer<T>) -> Option<&mut Self>` | Subtracts 1 from the value of the raw integer field `packer`, returning `None` (and leaving `self` unchanged) if the field is 0. |
+/// | `fn build(f: impl FnOnce(&mut Self)) -> Self` | Returns a new instance of the bitfield type constructed by `new`, after calling `f` with a mutable reference to it, so that all of a bitfield's fields may be set in a single block. |
 /// | `fn get<U>(&self, packer: Self::Packer<U>) -> U` | Given one of this type's generated packing specs for a `U`-typed value, unpacks the bit range represented by that value as a `U` and returns it. This method panics if the requested bit range does not contain a valid bit pattern for a `U`-typed value, as determined by `U`'s implementation of the [`FromBits`] trait. |
 /// | `fn try_get<U>(&self, packer: Self::Packer<U>) -> Result<U, <U as FromBits>::Error>` | Like `get`, but returns a `Result` instead of panicking. |
-/// | `fn assert_valid()` | Asserts that the generated bitfield type is valid. This is primarily intended to be used in tests; the macro cannot generate tests for a bitfield type on its own, so a test that simply calls `assert_valid` can be added to check the bitfield type's validity. |
+/// | `fn get_opt<U>(&self, packer: Self::Packer<U>) -> Option<U>` | Like `get`, but returns `None` instead of panicking. |
+/// | `fn get_range(&self, lsb: u32, len: u32) -> T` | Returns the raw bits in the `len`-bit range starting at bit `lsb`, where the range is computed at runtime. Panics if the range does not fit in a `T`. |
+/// | `fn set_range(&mut self, lsb: u32, len: u32, value: T) -> &mut Self` | Packs the raw bits in `value` into the `len`-bit range starting at bit `lsb`, where the range is computed at runtime. Panics if the range does not fit in a `T`, or if `value` does not fit in `len` bits. |
+/// | `fn bit(&self, n: u32) -> bool` | Returns `true` if bit `n` is set, regardless of field declarations. Panics if `n` is not less than the number of bits in a `T`. |
+/// | `fn set_bit(&mut self, n: u32, value: bool) -> &mut Self` | Sets bit `n` to `value`, regardless of field declarations. Panics if `n` is not less than the number of bits in a `T`. |
+/// | `fn set_bit_indices(&self) -> impl Iterator<Item = u32>` | Returns an iterator over the index of every set bit in the bitfield's raw bits, from least- to most-significant, regardless of field declarations. |
+/// | `const fn is_empty(&self) -> bool` | Returns `true` if no bits are set, other than those set by `new()` (i.e. fields declared with [`must_be`](#required-values)). |
+/// | `const fn any(&self) -> bool` | Returns `true` if any bits are set, other than those set by `new()`. This is the inverse of `is_empty`. |
+/// | `const fn count_ones(&self) -> u32` | Returns the number of ones in the bitfield's raw bits, regardless of field declarations. |
+/// | `const fn count_zeros(&self) -> u32` | Returns the number of zeros in the bitfield's raw bits, regardless of field declarations. |
+/// | `fn contains(&self, flag: Self::Packer<bool>) -> bool` | Returns `true` if the single-bit flag `flag` is set. Equivalent to `get(flag)`. |
+/// | `fn insert(&mut self, flag: Self::Packer<bool>) -> &mut Self` | Sets the single-bit flag `flag`. Equivalent to `set(flag, true)`. |
+/// | `fn remove(&mut self, flag: Self::Packer<bool>) -> &mut Self` | Clears the single-bit flag `flag`. Equivalent to `set(flag, false)`. |
+/// | `fn toggle(&mut self, flag: Self::Packer<bool>) -> &mut Self` | Inverts the single-bit flag `flag`. |
+/// | `fn record_fields(&self, f: impl FnMut(&'static str, &dyn fmt::Debug))` | Calls `f` with the name and unpacked value of each non-reserved field, from least- to most-significant. Typed fields are passed as their typed values, so their own `Debug` implementations are used. This is useful for recording each field separately in structured logging. |
+/// | `const fn field_docs() -> &'static [(&'static str, &'static str)]` | Returns the name and doc comment of each field, including reserved fields, from least- to most-significant. Fields without doc comments have empty documentation. |
+/// | `fn fields(&self) -> impl Iterator<Item = (&'static str, T)>` | Returns an iterator over the name and raw bits of each non-reserved field, from least- to most-significant. |
+/// | `fn diff(&self, other: &Self) -> impl Iterator<Item = (&'static str, T, T)>` | Returns an iterator over the name, old raw bits, and new raw bits of each non-reserved field whose value differs between `self` and `other`, from least- to most-significant. |
+/// | `const fn assert_valid_const()` | Asserts that the layout of the generated bitfield type is valid, in a `const` context. The macro calls this function at compile time, so an invalid layout is always a compile error. |
+/// | `fn assert_valid()` | Asserts that the generated bitfield type is valid, and that each typed field is exactly as wide as its type's `FromBits::BITS`. This is primarily intended to be used in tests; the macro cannot generate tests for a bitfield type on its own, so a test that simply calls `assert_valid` can be added to check the bitfield type's validity. |
 ///
 /// The visibility of these methods depends on the visibility of the bitfield
 /// struct --- if the struct is defined as `pub(crate) struct MyBitfield<u16> {
@@ -41,17 +72,222 @@
 ///
 /// | Trait | Description |
 /// |:--|:--|
-/// | [`fmt::Debug`] | The `Debug` implementation prints the bitfield as a "struct", with a "field" for each packing spec in the bitfield. If any of the bitfield's packing specs pack typed values, that type's [`fmt::Debug`] implementation is used rather than printing the value as an integer. |
+/// | [`fmt::Debug`] | The `Debug` implementation prints the bitfield as a "struct", with a "field" for each packing spec in the bitfield. If any of the bitfield's packing specs pack typed values, that type's [`fmt::Debug`] implementation is used rather than printing the value as an integer. If a typed field contains an invalid bit pattern for its type, its raw bits are printed as `Err(0b...)`, so formatting a bitfield never panics. |
 /// | [`fmt::Binary`] | Prints the raw bits of this bitfield as a binary number. |
-/// | [`fmt::Display`] | Pretty-prints the bitfield in a very nice-looking multi-line format which I'm rather proud of. See [here](#example-display-output) for examples of this format. |
+/// | [`fmt::Display`] | Pretty-prints the bitfield in a very nice-looking multi-line format which I'm rather proud of. See [here](#example-display-output) for examples of this format. The alternate (`{:#}`) form prints a compact, single-line summary instead, the `+` flag (`{:+}`) marks bits not covered by any declared field with `?`, the `-` flag (`{:-}`) labels each field with its bit range, numbered MSB-first, a precision (`{:.N}`) splits the diagram into rows of `N` bits, grouped into bytes, and a width (`{:N}`) narrower than the bitfield truncates the diagram to `N` columns. |
+/// | [`FromBits`] | Implemented for the bitfield's integer type `T` and every wider unsigned integer type, so that a bitfield type may be used as a typed field in another bitfield. |
+/// | [`TryFrom<T>`] | Converts a `T` into an instance of the bitfield type, checking that every typed field contains a valid bit pattern for its type. If a field is invalid, an [`InvalidField`] error naming the first invalid field is returned. Unlike `from_bits`, this fails at the boundary rather than when the field is accessed. |
+/// | [`From<Self>`] for `T` | Converts an instance of the bitfield type into its raw bits, as if by `bits`. The inverse `From<T>` conversion is not generated, as it would conflict with the `TryFrom<T>` implementation; use `from_bits` (or [`BitfieldExt::from_bits`]) to convert a `T` into the bitfield type without checking its typed fields. |
+/// | [`BitfieldExt`] | Provides the `bits` and `from_bits` methods as trait methods, so that code may be generic over multiple bitfield types. |
 /// | [`Copy`] | Behaves identically as the [`Copy`] implementation for the underlying integer type. |
 /// | [`Clone`] | Behaves identically as the [`Clone`] implementation for the underlying integer type. |
 ///
+/// If the "serde" feature flag is enabled, `serde::Serialize` and
+/// `serde::Deserialize` implementations are also generated. These
+/// implementations serialize a bitfield transparently, as its underlying
+/// integer value.
+///
+/// If the "tracing" feature flag is enabled, a `fn as_value(&self)` method is
+/// also generated, returning a `tracing::Value` which records
+/// the bitfield using its `Debug` implementation. This allows bitfields to be
+/// recorded as `tracing` fields without a `?` sigil, e.g.
+/// `tracing::info!(reg = my_bitfield.as_value())`. Because `tracing`'s `Value`
+/// trait is sealed, it cannot be implemented for bitfield types directly.
+///
+/// If the "arbitrary" feature flag is enabled, an `arbitrary::Arbitrary`
+/// implementation is also generated, for use in fuzzing and property tests.
+/// This implementation generates an arbitrary value of the underlying
+/// integer type and clears any bits which are not part of a declared field,
+/// as if by `from_bits_truncate`. If a typed field would then contain an
+/// invalid bit pattern for its type, the input is rejected with
+/// `arbitrary::Error::IncorrectFormat`, so that fuzzers only produce
+/// bitfields whose typed fields are all valid.
+///
 /// Additional traits may be derived for the bitfield type, such as
-/// [`PartialEq`], [`Eq`], and [`Default`]. These traits are not automatically
-/// derived, as custom implementations may also be desired, depending on the
-/// use-case. For example, the `Default` value for a bitfield may _not_ be all
-/// zeroes.
+/// [`PartialEq`], [`Eq`], [`Hash`], and [`Default`]. These traits are not
+/// automatically derived, as custom implementations may also be desired,
+/// depending on the use-case. For example, the `Default` value for a bitfield
+/// may _not_ be all zeroes; the [`default` option](#options) generates a
+/// `Default` implementation with a specified initial value for each field.
+/// Derived implementations operate on the bitfield's
+/// underlying integer, so, for example, a bitfield type with
+/// `#[derive(Eq, PartialEq, Hash)]` may be used as a `HashMap` key.
+///
+/// # Options
+///
+/// Some additional code generation can be enabled by adding a
+/// `#[bitfield(...)]` attribute to the bitfield struct, containing a
+/// comma-separated list of options. The following options are supported:
+///
+/// | Option | Description |
+/// |:--|:--|
+/// | `partial_eq_bits` | Generates [`PartialEq`] implementations comparing the bitfield type with its underlying integer type `T` (in both directions), so that a bitfield may be compared directly against a raw integer value. |
+/// | `bitwise_ops` | Generates [`BitAnd`], [`BitOr`], and [`BitXor`] implementations (and their `*Assign` variants) between two values of the bitfield type, which operate on the underlying integers. This is primarily useful for bitfields consisting of single-bit flags. |
+/// | `default(FIELD = value, ...)` | Generates a [`Default`] implementation which packs each listed `value` into the corresponding `FIELD`. Bits not covered by a listed field are zero. This is useful for hardware registers whose reset value is not all zeroes. |
+/// | `decode(Fields)` | Generates a struct named `Fields`, with a field for each of the bitfield's fields holding its unpacked value, and a `fn decode(&self) -> Fields` method which unpacks every field at once. This is useful for pattern-matching on several fields at a time. Like `get`, `decode` panics if a typed field does not contain a valid bit pattern. |
+/// | `masks(Masks)` | Generates a unit struct named `Masks`, with an associated constant for each of the bitfield's fields. Each constant is a value of the bitfield type with every bit of that field set, and is named after the field. Since the packing specs are already named after the fields, the constants live on `Masks` rather than on the bitfield type, so that `Masks::FIELD` and `MyBitfield::FIELD` do not collide. Combined with `bitwise_ops`, this allows flag sets to be built with `|`. |
+/// | `bits_accessor(name)` | Names the generated method returning the bitfield's raw bits `name`, rather than `bits`. This is useful if `bits` would collide with another method defined for the bitfield type, such as one returning the number of bits in a field named `BITS`. The [`BitfieldExt::bits`] trait method is still implemented, and returns the same value. |
+/// | `no_debug` | Skips generating the [`fmt::Debug`] implementation, so that a custom implementation may be provided (for example, to redact a field's value in logs). The `FIELDS` constant is private to the module defining the bitfield, so a custom implementation should use the `get` and `try_get` methods, or the `record_fields` and `fields` methods to access the bitfield's fields. Note that the `tracing` feature's `as_value` method and the `decode` option's generated struct require a `Debug` implementation. |
+/// | `no_display` | Skips generating the [`fmt::Display`] implementation, so that a custom implementation may be provided. |
+/// | `exhaustive` | Asserts that the bitfield's fields (including reserved fields) fill every bit of its integer type `T`, so that a layout which leaves bits undeclared is a compile error. This is useful for hardware registers, where an unaccounted-for bit usually means a field was forgotten. For bitfields with const generic parameters, the assertion is made by `assert_valid_const` and `assert_valid` instead. |
+///
+/// For example:
+///
+/// ```
+/// mycelium_bitfield::bitfield! {
+///     #[bitfield(partial_eq_bits)]
+///     #[derive(Eq, PartialEq)]
+///     pub struct MyBitfield<u8> {
+///         pub const FOO = 4;
+///         pub const BAR = 4;
+///     }
+/// }
+///
+/// let bitfield = MyBitfield::new().with(MyBitfield::BAR, 0b1010);
+///
+/// assert_eq!(bitfield, 0b1010_0000);
+/// assert_eq!(0b1010_0000, bitfield);
+/// ```
+///
+/// Using the `default` option to specify a register's reset value:
+///
+/// ```
+/// mycelium_bitfield::bitfield! {
+///     #[bitfield(default(ENABLED = true, DIVISOR = 0b110))]
+///     pub struct TimerConfig<u8> {
+///         pub const ENABLED: bool;
+///         pub const ONESHOT: bool;
+///         pub const DIVISOR = 4;
+///     }
+/// }
+///
+/// let config = TimerConfig::default();
+/// assert!(config.get(TimerConfig::ENABLED));
+/// assert!(!config.get(TimerConfig::ONESHOT));
+/// assert_eq!(config.get(TimerConfig::DIVISOR), 0b110);
+/// assert_eq!(config.bits(), 0b0001_1001);
+/// ```
+///
+/// Using the `decode` option to unpack every field at once:
+///
+/// ```
+/// mycelium_bitfield::bitfield! {
+///     #[bitfield(decode(StatusFields))]
+///     pub struct Status<u8> {
+///         pub const READY: bool;
+///         pub const ERROR: bool;
+///         pub const CODE = 6;
+///     }
+/// }
+///
+/// let status = Status::new().with(Status::ERROR, true).with(Status::CODE, 7);
+/// match status.decode() {
+///     StatusFields { ERROR: true, CODE, .. } => assert_eq!(CODE, 7),
+///     fields => unreachable!("unexpected status: {fields:?}"),
+/// }
+/// ```
+///
+/// Using the `masks` option with `bitwise_ops` to combine flags:
+///
+/// ```
+/// mycelium_bitfield::bitfield! {
+///     #[bitfield(bitwise_ops, masks(PermMasks))]
+///     #[derive(Eq, PartialEq)]
+///     pub struct Perms<u8> {
+///         pub const READ: bool;
+///         pub const WRITE: bool;
+///         pub const EXEC: bool;
+///     }
+/// }
+///
+/// let mut perms = PermMasks::READ | PermMasks::WRITE;
+/// assert!(perms.get(Perms::READ));
+/// assert!(perms.get(Perms::WRITE));
+/// assert!(!perms.get(Perms::EXEC));
+///
+/// perms |= PermMasks::EXEC;
+/// assert_eq!(perms.bits(), 0b111);
+/// ```
+///
+/// Using the `bits_accessor` option to rename the `bits` method:
+///
+/// ```
+/// mycelium_bitfield::bitfield! {
+///     #[bitfield(bits_accessor(raw))]
+///     pub struct Sample<u16> {
+///         /// The number of significant bits in `VALUE`.
+///         pub const BITS = 4;
+///         pub const VALUE = 12;
+///     }
+/// }
+///
+/// impl Sample {
+///     /// Returns the number of significant bits in this sample.
+///     pub fn bits(&self) -> u16 {
+///         self.get(Self::BITS)
+///     }
+/// }
+///
+/// let sample = Sample::new().with(Sample::BITS, 8).with(Sample::VALUE, 0xff);
+/// assert_eq!(sample.bits(), 8);
+/// assert_eq!(sample.raw(), 0xff8);
+/// ```
+///
+/// Using the `no_debug` option to provide a custom `Debug` implementation:
+///
+/// ```
+/// use core::fmt;
+///
+/// mycelium_bitfield::bitfield! {
+///     #[bitfield(no_debug)]
+///     pub struct Credentials<u32> {
+///         pub const USER = 16;
+///         pub const SECRET = 16;
+///     }
+/// }
+///
+/// impl fmt::Debug for Credentials {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         f.debug_struct("Credentials")
+///             .field("USER", &self.get(Self::USER))
+///             .field("SECRET", &format_args!("<redacted>"))
+///             .finish()
+///     }
+/// }
+///
+/// let creds = Credentials::new()
+///     .with(Credentials::USER, 1000)
+///     .with(Credentials::SECRET, 0xC0DE);
+/// assert_eq!(
+///     format!("{creds:?}"),
+///     "Credentials { USER: 1000, SECRET: <redacted> }",
+/// );
+/// ```
+///
+/// Using the `exhaustive` option to require that every bit is declared:
+///
+/// ```
+/// mycelium_bitfield::bitfield! {
+///     #[bitfield(exhaustive)]
+///     pub struct Control<u8> {
+///         pub const ENABLE: bool;
+///         const _RESERVED = 3;
+///         pub const MODE = 4;
+///     }
+/// }
+/// ```
+///
+/// A bitfield whose fields leave some bits undeclared fails to compile:
+///
+/// ```compile_fail
+/// mycelium_bitfield::bitfield! {
+///     #[bitfield(exhaustive)]
+///     pub struct Control<u8> {
+///         pub const ENABLE: bool;
+///         pub const MODE = 4;
+///     }
+/// }
+/// ```
 ///
 /// # Examples
 ///
@@ -171,6 +407,33 @@
 /// assert!(invalid.try_get(TypedBitfield::ENUM_VALUE).is_err());
 /// ```
 ///
+/// Because every bitfield type implements [`FromBits`], bitfields may also be
+/// nested inside other bitfields:
+///
+/// ```
+/// use mycelium_bitfield::bitfield;
+///
+/// bitfield! {
+///     #[derive(Eq, PartialEq)]
+///     pub struct Inner<u8> {
+///         pub const LOW = 4;
+///         pub const HIGH = 4;
+///     }
+/// }
+///
+/// bitfield! {
+///     pub struct Outer<u16> {
+///         pub const FLAGS = 8;
+///         pub const INNER: Inner;
+///     }
+/// }
+///
+/// let outer = Outer::from_bits(0xAB_00);
+/// let inner = outer.get(Outer::INNER);
+/// assert_eq!(inner, Inner::from_bits(0xAB));
+/// assert_eq!(inner.get(Inner::HIGH), 0xA);
+/// ```
+///
 /// Packing specs from one bitfield type may *not* be used with a different
 /// bitfield type's `get`, `set`, or `with` methods. For example, the following
 /// is a type error:
@@ -200,6 +463,312 @@
 /// let bits = Bitfield1::new().with(Bitfield2::ALICE, 0b11);
 /// ```
 ///
+/// Declaring fields whose total width exceeds the number of bits in the
+/// bitfield's integer type is a compile-time error. The error will name the
+/// first field that does not fit. For example:
+///
+/// ```compile_fail
+/// mycelium_bitfield::bitfield! {
+///     struct TooBig<u8> {
+///         const FOO = 4;
+///         const BAR: bool;
+///         // Only 3 bits remain, so this field does not fit!
+///         const BAZ = 4;
+///     }
+/// }
+/// ```
+///
+/// The last field in a bitfield may be declared with `= ..` instead of a
+/// width, in which case it occupies all of the remaining most-significant
+/// bits. Its width is the number of bits in `T`, minus the total width of all
+/// previous fields:
+///
+/// ```
+/// mycelium_bitfield::bitfield! {
+///     pub struct MyBitfield<u16> {
+///         pub const LOW = 4;
+///         pub const FLAG: bool;
+///         // Claims the remaining 11 bits.
+///         pub const REST = ..;
+///     }
+/// }
+///
+/// assert_eq!(MyBitfield::REST.bits(), 16 - 5);
+///
+/// let bitfield = MyBitfield::from_bits(0xFFFF);
+/// assert_eq!(bitfield.get(MyBitfield::REST), 0b111_1111_1111);
+/// ```
+///
+/// A `= ..` field must be the last field in the bitfield, and it is a
+/// compile-time error if the previous fields have already used all the bits in
+/// `T`.
+///
+/// Fields must be at least one bit wide, so declaring a zero-width field is a
+/// compile-time error:
+///
+/// ```compile_fail
+/// mycelium_bitfield::bitfield! {
+///     struct ZeroWidth<u8> {
+///         const FOO = 4;
+///         // Oops, this should have been the width of the next field!
+///         const BAR = 0;
+///         const BAZ = 4;
+///     }
+/// }
+/// ```
+///
+/// By default, each field is packed into the bits immediately following the
+/// previous field. A field may instead be placed at an explicit bit offset by
+/// adding `@ N` after its name, where `N` is the index of the field's
+/// least-significant bit (counting from 0). This is useful when transcribing a
+/// register layout from a datasheet. Any bits skipped over by an explicitly
+/// placed field are left unused, and subsequent fields without an offset are
+/// packed immediately after it:
+///
+/// ```
+/// mycelium_bitfield::bitfield! {
+///     #[derive(Eq, PartialEq)]
+///     pub struct Register<u16> {
+///         pub const ENABLE: bool;
+///         // Bits 1-11 are unused.
+///         pub const MODE @ 12 = 3;
+///         // Packed starting at bit 15, immediately after `MODE`.
+///         pub const LOCK: bool;
+///     }
+/// }
+///
+/// let register = Register::new()
+///     .with(Register::MODE, 0b101)
+///     .with(Register::LOCK, true);
+///
+/// assert_eq!(register, Register::from_bits(0b1101 << 12));
+/// # Register::assert_valid();
+/// ```
+///
+/// Explicitly placed fields must still be declared in order from least- to
+/// most-significant. A field whose offset would overlap the previous field is
+/// a compile-time error:
+///
+/// ```compile_fail
+/// mycelium_bitfield::bitfield! {
+///     struct Overlapping<u16> {
+///         const FOO @ 4 = 4;
+///         // `FOO` occupies bits 4-7, so this overlaps it!
+///         const BAR @ 6 = 2;
+///     }
+/// }
+/// ```
+///
+/// Because each field's packing spec is an associated `const`, and the
+/// packing spec's [`offset`] and [`width`] methods are `const fn`s, a
+/// bitfield's layout can be pinned against a datasheet with compile-time
+/// assertions. If the layout changes, these become compile errors:
+///
+/// ```
+/// # mycelium_bitfield::bitfield! {
+/// #     #[derive(Eq, PartialEq)]
+/// #     pub struct Register<u16> {
+/// #         pub const ENABLE: bool;
+/// #         pub const MODE @ 12 = 3;
+/// #         pub const LOCK: bool;
+/// #     }
+/// # }
+/// const _: () = {
+///     assert!(Register::ENABLE.offset() == 0);
+///     assert!(Register::MODE.offset() == 12);
+///     assert!(Register::MODE.width() == 3);
+///     assert!(Register::LOCK.offset() == 15);
+/// };
+/// ```
+///
+/// ## Split Fields
+///
+/// Some registers, such as those in paging structures, split a single value
+/// across multiple ranges of bits, separated by other fields. A raw integer
+/// field may be declared with a list of widths in square brackets, rather than
+/// a single width. Each range is packed immediately after the previous one, or
+/// at an explicit offset if one is given with `@ N`. The field's value is
+/// reassembled from its ranges in order, so the first range contains the
+/// value's least-significant bits:
+///
+/// ```
+/// mycelium_bitfield::bitfield! {
+///     pub struct Entry<u16> {
+///         pub const PRESENT: bool;
+///         // 4 low bits in bits 1-4, and 8 high bits in bits 8-15.
+///         pub const ADDR = [4, 8 @ 8];
+///         // Fields may be placed in the gap between a split field's ranges.
+///         pub const FLAGS @ 5 = 3;
+///     }
+/// }
+///
+/// let entry = Entry::new().with(Entry::ADDR, 0xABC);
+/// assert_eq!(entry.bits(), 0xAB << 8 | 0xC << 1);
+/// assert_eq!(entry.get(Entry::ADDR), 0xABC);
+/// # Entry::assert_valid();
+/// ```
+///
+/// A field placed in a split field's gap must be declared after the split
+/// field, and it is a compile-time error for the field to overlap any of the
+/// split field's ranges. The [`fmt::Display`] implementation connects each of
+/// a split field's ranges to a single label.
+///
+/// ## Required Values
+///
+/// Some hardware requires that reserved bits always be written with a
+/// particular value, which may not be zero. A raw integer field may be given
+/// a required value by adding `must_be(VALUE)` after its width. The bitfield
+/// type's `new()` constructor (and the `default` [option](#options)) will
+/// set that field to `VALUE`, rather than zero. Combined with a reserved
+/// (`_`-prefixed) name, this allows "must be one" bits to be written
+/// correctly without appearing in the bitfield's `Debug` and `Display`
+/// output:
+///
+/// ```
+/// mycelium_bitfield::bitfield! {
+///     pub struct Control<u8> {
+///         pub const ENABLE: bool;
+///         // The datasheet says these bits must always be written as 1s.
+///         const _MBO = 2 must_be(0b11);
+///         pub const MODE = 5;
+///     }
+/// }
+///
+/// let control = Control::new().with(Control::ENABLE, true);
+/// assert_eq!(control.bits(), 0b0000_0111);
+/// // `from_bits` and `bits` preserve whatever value the bits actually have.
+/// assert_eq!(Control::from_bits(0).bits(), 0);
+/// ```
+///
+/// It is a compile-time error if the required value does not fit in the
+/// field.
+///
+/// ## Field Annotations
+///
+/// A field may be annotated with a string describing its value, such as its
+/// units, by adding `=> "annotation"` at the end of its declaration. The
+/// annotation is included in the field's label in the bitfield's
+/// [`fmt::Display`] output, before the field's raw bits:
+///
+/// ```
+/// mycelium_bitfield::bitfield! {
+///     pub struct Timer<u8> {
+///         pub const ENABLE: bool;
+///         pub const DIVISOR = 4 => "divisor";
+///     }
+/// }
+///
+/// let timer = Timer::new().with(Timer::DIVISOR, 8);
+/// let expected = r#"
+/// 00010000
+///    └┬─┘│
+///     │  └ ENABLE: false (0)
+///     └── DIVISOR: 8 (divisor, 1000)
+/// "#.trim_start();
+/// assert_eq!(format!("{timer}"), expected);
+/// ```
+///
+/// ## Signed Bitfields
+///
+/// Bitfields may also be declared with a signed integer type (`i8`, `i16`,
+/// `i32`, `i64`, `i128`, or `isize`). This is useful for hardware registers
+/// which are naturally represented as two's-complement values. A signed
+/// bitfield is constructed from (and compared with) its signed integer type,
+/// but its bits are stored and packed as the unsigned integer type of the
+/// same width. Therefore, untyped fields are unpacked as unsigned integers,
+/// typed fields must implement `FromBits` for the unsigned type, and the
+/// [`fmt::Display`] and [`fmt::Binary`] implementations print the raw
+/// two's-complement bits. A sub-range of the bitfield may be unpacked as a
+/// signed value by declaring it with a signed typed field:
+///
+/// ```
+/// mycelium_bitfield::bitfield! {
+///     pub struct Signed<i16> {
+///         pub const LOW = 8;
+///         pub const HIGH: i8;
+///     }
+/// }
+///
+/// let bitfield = Signed::from_bits(-2);
+/// assert_eq!(bitfield.get(Signed::LOW), 0xFE);
+/// assert_eq!(bitfield.get(Signed::HIGH), -1);
+/// ```
+///
+/// ## Qualified Integer Types
+///
+/// The bitfield's integer type may also be written as a path to one of the
+/// primitive integer types in `core::primitive` or `std::primitive`, such as
+/// `core::primitive::u16`. This is useful when a bitfield is declared by
+/// another macro which emits fully-qualified type paths:
+///
+/// ```
+/// mycelium_bitfield::bitfield! {
+///     pub struct Qualified<::core::primitive::u16> {
+///         pub const LOW = 4;
+///         pub const HIGH = ..;
+///     }
+/// }
+///
+/// let bitfield = Qualified::from_bits(0xfeed);
+/// assert_eq!(bitfield.get(Qualified::LOW), 0xd);
+/// assert_eq!(bitfield.get(Qualified::HIGH), 0xfee);
+/// ```
+///
+/// Because macros cannot resolve type aliases, a bitfield may not be declared
+/// with an alias of a primitive integer type.
+///
+/// ```compile_fail
+/// type MyU32 = u32;
+///
+/// mycelium_bitfield::bitfield! {
+///     pub struct Aliased<MyU32> {
+///         pub const LOW = 4;
+///     }
+/// }
+/// ```
+///
+/// ## Const Generic Field Widths
+///
+/// A family of bitfield types which differ only in the width of their raw
+/// integer fields may be declared as a single type with `const` generic
+/// parameters, following the bitfield's integer type. A `const` parameter may
+/// then be used as the width of a raw integer field:
+///
+/// ```
+/// mycelium_bitfield::bitfield! {
+///     pub struct Counter<u32, const W: u32> {
+///         pub const ENABLE: bool;
+///         pub const COUNT = W;
+///         pub const MODE = 2;
+///     }
+/// }
+///
+/// type Counter8 = Counter<8>;
+/// type Counter16 = Counter<16>;
+///
+/// let small = Counter8::new().with(Counter8::MODE, 0b11);
+/// assert_eq!(small.bits(), 0b11 << 9);
+///
+/// let large = Counter16::new().with(Counter16::MODE, 0b11);
+/// assert_eq!(large.bits(), 0b11 << 17);
+/// # Counter8::assert_valid();
+/// # Counter16::assert_valid();
+/// ```
+///
+/// This support is currently limited:
+///
+/// - Only `const` generic parameters are supported; bitfields may not have
+///   type or lifetime parameters.
+/// - A `const` parameter may only be used as the width of a raw integer
+///   field (`const FIELD = W;` or `const FIELD @ N = W;`). It may not be used
+///   as a field's offset, or as the width of a range in a split field.
+/// - Because the layout of a generic bitfield depends on its parameters, it
+///   cannot be checked where the type is declared. Instead, each packing spec
+///   is checked at compile time when it is used with particular parameters,
+///   so an invalid layout is a compile error at the use site. Calling
+///   `assert_valid` in a test for each instantiation of the type checks
+///   every field, including fields which are not otherwise used.
+///
 /// ## Example `Display` Output
 ///
 /// Bitfields will automatically generate a pretty [`fmt::Display`]
@@ -256,69 +825,366 @@
 /// "#.trim_start();
 /// assert_eq!(formatted, expected);
 /// ```
-/// [`fmt::Debug`]: core::fmt::Debug
-/// [`fmt::Display`]: core::fmt::Display
-/// [`fmt::Binary`]: core::fmt::Binary
-/// [transparent]: https://doc.rust-lang.org/reference/type-layout.html#the-transparent-representation
-/// [`example`]: crate::example
-/// [`ExampleBitfield`]: crate::example::ExampleBitfield
-/// [`FromBits`]: crate::FromBits
-#[macro_export]
-macro_rules! bitfield {
-    (
-        $(#[$($meta:meta)+])*
-        $vis:vis struct $Name:ident<$T:ident> {
-            $(
-                $(#[$field_meta:meta])*
-                $field_vis:vis const $Field:ident $(: $F:ty)? $( = $val:tt)?;
-            )+
-        }
-    ) => {
-        $(#[$($meta)+])*
-        #[derive(Copy, Clone)]
-        #[repr(transparent)]
-        $vis struct $Name($T);
-
-        #[automatically_derived]
-        impl core::fmt::Debug for $Name {
-            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-                let mut dbg = f.debug_struct(stringify!($Name));
-                $(
-                    dbg.field(stringify!($Field), &self.get(Self::$Field));
-                )+
-                dbg.finish()
-
-            }
-        }
-
-        // Some generated methods may not always be used, which may emit dead
-        // code warnings if the type is private.
-        #[allow(dead_code)]
-        #[automatically_derived]
-        impl $Name {
-            $crate::bitfield! { @field<$T>:
-                $(
-                    $(#[$field_meta])*
-                    $field_vis const $Field $(: $F)? $( = $val)?;
-                )+
-            }
-
-            const FIELDS: &'static [(&'static str, $crate::bitfield! { @t $T, $T, Self })] = &[$(
-                (stringify!($Field), Self::$Field.typed())
-            ),+];
-
-            /// Constructs a new instance of `Self` from the provided raw bits.
-            $vis const fn from_bits(bits: $T) -> Self {
-                Self(bits)
-            }
-
-            /// Constructs a new instance of `Self` with all bits set to 0.
-            $vis const fn new() -> Self {
-                Self(0)
-            }
-
-            /// Packs the bit representation of `value` into `self` at the bit
-            /// range designated by `field`, returning a new bitfield.
+///
+/// The diagram can be rather large, so the alternate (`{:#}`) form of the
+/// `Display` implementation instead prints a compact single-line summary of
+/// the bitfield's fields, which is more suitable for log messages:
+///
+/// ```
+/// # use mycelium_bitfield::bitfield;
+/// # bitfield! {
+/// #      pub struct MyBitfield<u16> {
+/// #          pub const HELLO = 6;
+/// #          const _RESERVED = 4;
+/// #          pub const WORLD: bool;
+/// #      }
+/// # }
+/// let my_bitfield = MyBitfield::new()
+///     .with(MyBitfield::HELLO, 0b10_1010)
+///     .with(MyBitfield::WORLD, true);
+/// assert_eq!(
+///     format!("{my_bitfield:#}"),
+///     "MyBitfield { HELLO: 42, WORLD: true }",
+/// );
+/// ```
+///
+/// When reverse-engineering hardware registers, it can be useful to see which
+/// bits are not described by any of the bitfield's fields. The `+` flag
+/// (`{:+}`) marks every bit that is not part of a declared field with a `?`.
+/// Reserved fields (whose names begin with `_`) are considered declared:
+///
+/// ```
+/// # use mycelium_bitfield::bitfield;
+/// bitfield! {
+///     pub struct Gappy<u16> {
+///         pub const LOW = 3;
+///         const _RESERVED = 2;
+///         pub const FLAG @ 8: bool;
+///     }
+/// }
+///
+/// let gappy = Gappy::new().with(Gappy::LOW, 0b101).with(Gappy::FLAG, true);
+/// let expected = r#"
+/// 0000000100000101
+/// ???????│???  └┬┘
+///        │      └─ LOW: 5 (101)
+///        └─────── FLAG: true (1)
+/// "#.trim_start();
+/// assert_eq!(format!("{gappy:+}"), expected);
+/// ```
+///
+/// Some datasheets number bits "MSB-0" (or "IBM-style"), where bit 0 is the
+/// *most*-significant bit. To make it easier to compare the diagram against
+/// such a datasheet, the `-` flag (`{:-}`) labels each field with the range
+/// of bits it occupies, numbered MSB-first:
+///
+/// ```
+/// # use mycelium_bitfield::bitfield;
+/// # bitfield! {
+/// #     pub struct Gappy<u16> {
+/// #         pub const LOW = 3;
+/// #         const _RESERVED = 2;
+/// #         pub const FLAG @ 8: bool;
+/// #     }
+/// # }
+/// let gappy = Gappy::new().with(Gappy::LOW, 0b101).with(Gappy::FLAG, true);
+/// let expected = r#"
+/// 0000000100000101
+///        │     └┬┘
+///        │      └─ LOW: 5 (101) [13:15]
+///        └─────── FLAG: true (1) [7]
+/// "#.trim_start();
+/// assert_eq!(format!("{gappy:-}"), expected);
+/// ```
+///
+/// The diagram for a wide bitfield may not fit on one line of a serial
+/// console. Formatting with a precision (`{:.N}`) splits the diagram into
+/// rows of `N` bits (rounded up to a whole number of bytes), starting with the
+/// most-significant bits, and separates each byte with a space, much like
+/// the register diagrams in a datasheet. A field which spans multiple rows is
+/// labeled in each row:
+///
+/// ```
+/// # use mycelium_bitfield::bitfield;
+/// bitfield! {
+///     pub struct Wide<u32> {
+///         pub const LOW = 4;
+///         pub const MID = 16;
+///         pub const HIGH = ..;
+///     }
+/// }
+///
+/// let wide = Wide::new()
+///     .with(Wide::LOW, 0b1001)
+///     .with(Wide::MID, 0xBEEF)
+///     .with(Wide::HIGH, 0xAB);
+/// let expected = r#"
+/// 00001010 10111011
+/// └┬──────────┘└┬─┘
+///  │            └─ MID: 48879 (1011111011101111)
+///  └───────────── HIGH: 171 (000010101011)
+///
+/// 11101110 11111001
+/// └┬──────────┘└┬─┘
+///  │            └ LOW: 9 (1001)
+///  └───────────── MID: 48879 (1011111011101111)
+/// "#.trim_start();
+/// assert_eq!(format!("{wide:.16}"), expected);
+/// ```
+///
+/// Alternatively, a width (`{:N}`) which is narrower than the bitfield limits
+/// the diagram to `N` columns, so that it does not wrap in constrained log
+/// lines. Only the `N - 1` least-significant bits are drawn, following an
+/// ellipsis, and only the fields which fit in those bits are labeled. The
+/// number of fields which did not fit is noted at the end of the diagram:
+///
+/// ```
+/// # use mycelium_bitfield::bitfield;
+/// bitfield! {
+///     pub struct Status<u32> {
+///         pub const READY: bool;
+///         pub const ERROR: bool;
+///         pub const CODE = 6;
+///         pub const COUNT = 16;
+///         pub const MODE = 4;
+///     }
+/// }
+///
+/// let status = Status::new()
+///     .with(Status::READY, true)
+///     .with(Status::CODE, 0x2A)
+///     .with(Status::COUNT, 0xFFFF);
+/// let expected = r#"
+/// …10101001
+///  └┬───┘││
+///   │    │└ READY: true (1)
+///   │    └ ERROR: false (0)
+///   └────── CODE: 42 (101010)
+///  (+2 more)
+/// "#.trim_start();
+/// assert_eq!(format!("{status:9}"), expected);
+/// ```
+/// [`fmt::Debug`]: core::fmt::Debug
+/// [`fmt::Display`]: core::fmt::Display
+/// [`fmt::Binary`]: core::fmt::Binary
+/// [transparent]: https://doc.rust-lang.org/reference/type-layout.html#the-transparent-representation
+/// [`example`]: crate::example
+/// [`ExampleBitfield`]: crate::example::ExampleBitfield
+/// [`FromBits`]: crate::FromBits
+/// [`OutOfRange`]: crate::OutOfRange
+/// [`InvalidField`]: crate::InvalidField
+/// [`offset`]: crate::Pack64::offset
+/// [`width`]: crate::Pack64::width
+/// [`BitfieldExt`]: crate::BitfieldExt
+/// [`TryFrom<T>`]: core::convert::TryFrom
+/// [`Cell::replace`]: core::cell::Cell::replace
+/// [`From<Self>`]: core::convert::From
+/// [`BitfieldExt::from_bits`]: crate::BitfieldExt::from_bits
+/// [`BitfieldExt::bits`]: crate::BitfieldExt::bits
+/// [`Hash`]: core::hash::Hash
+/// [`BitAnd`]: core::ops::BitAnd
+/// [`BitOr`]: core::ops::BitOr
+/// [`BitXor`]: core::ops::BitXor
+#[macro_export]
+macro_rules! bitfield {
+    // Paths to primitive integer types are replaced with the type's name, so
+    // that the rest of the macro may match on it.
+    (
+        $(#[$($attr:tt)*])*
+        $vis:vis struct $Name:ident<$(::)? core::primitive::$T:ident $(, $(const $G:ident: $GT:ty),+ $(,)?)?> { $($body:tt)* }
+    ) => {
+        $crate::bitfield! {
+            $(#[$($attr)*])*
+            $vis struct $Name<$T $(, $(const $G: $GT),+)?> { $($body)* }
+        }
+    };
+    (
+        $(#[$($attr:tt)*])*
+        $vis:vis struct $Name:ident<$(::)? std::primitive::$T:ident $(, $(const $G:ident: $GT:ty),+ $(,)?)?> { $($body:tt)* }
+    ) => {
+        $crate::bitfield! {
+            $(#[$($attr)*])*
+            $vis struct $Name<$T $(, $(const $G: $GT),+)?> { $($body)* }
+        }
+    };
+    (
+        $(#[$($attr:tt)*])*
+        $vis:vis struct $Name:ident<$T:ident> { $($body:tt)* }
+    ) => {
+        $crate::bitfield! {
+            @process_attrs [] [] []
+            $(#[$($attr)*])*
+            $vis struct $Name<$T> { $($body)* }
+        }
+    };
+    (
+        $(#[$($attr:tt)*])*
+        $vis:vis struct $Name:ident<$T:ident, $(const $G:ident: $GT:ty),+ $(,)?> { $($body:tt)* }
+    ) => {
+        $crate::bitfield! {
+            @process_attrs [] [] [$(const $G: $GT),+]
+            $(#[$($attr)*])*
+            $vis struct $Name<$T> { $($body)* }
+        }
+    };
+    // Strip `#[bitfield(...)]` attributes from the struct's attributes, and
+    // collect the options they contain.
+    (@process_attrs [$($attrs:tt)*] [$($opts:tt)*] [$($gen:tt)*]
+        #[bitfield($($opt:tt)*)]
+        $($rest:tt)*
+    ) => {
+        $crate::bitfield! { @process_attrs [$($attrs)*] [$($opts)* $($opt)* ,] [$($gen)*] $($rest)* }
+    };
+    (@process_attrs [$($attrs:tt)*] [$($opts:tt)*] [$($gen:tt)*]
+        #[$($attr:tt)*]
+        $($rest:tt)*
+    ) => {
+        $crate::bitfield! { @process_attrs [$($attrs)* #[$($attr)*]] [$($opts)*] [$($gen)*] $($rest)* }
+    };
+    // Bitfields backed by signed integers are stored as (and packed into) the
+    // unsigned integer type of the same width.
+    (@process_attrs [$($attrs:tt)*] [$($opts:tt)*] [$($gen:tt)*] $vis:vis struct $Name:ident<i8> { $($body:tt)* }) => {
+        $crate::bitfield! { @generate [$($attrs)*] [$($opts)*] [$($gen)*] $vis struct $Name<u8 as i8> { $($body)* } }
+    };
+    (@process_attrs [$($attrs:tt)*] [$($opts:tt)*] [$($gen:tt)*] $vis:vis struct $Name:ident<i16> { $($body:tt)* }) => {
+        $crate::bitfield! { @generate [$($attrs)*] [$($opts)*] [$($gen)*] $vis struct $Name<u16 as i16> { $($body)* } }
+    };
+    (@process_attrs [$($attrs:tt)*] [$($opts:tt)*] [$($gen:tt)*] $vis:vis struct $Name:ident<i32> { $($body:tt)* }) => {
+        $crate::bitfield! { @generate [$($attrs)*] [$($opts)*] [$($gen)*] $vis struct $Name<u32 as i32> { $($body)* } }
+    };
+    (@process_attrs [$($attrs:tt)*] [$($opts:tt)*] [$($gen:tt)*] $vis:vis struct $Name:ident<i64> { $($body:tt)* }) => {
+        $crate::bitfield! { @generate [$($attrs)*] [$($opts)*] [$($gen)*] $vis struct $Name<u64 as i64> { $($body)* } }
+    };
+    (@process_attrs [$($attrs:tt)*] [$($opts:tt)*] [$($gen:tt)*] $vis:vis struct $Name:ident<i128> { $($body:tt)* }) => {
+        $crate::bitfield! { @generate [$($attrs)*] [$($opts)*] [$($gen)*] $vis struct $Name<u128 as i128> { $($body)* } }
+    };
+    (@process_attrs [$($attrs:tt)*] [$($opts:tt)*] [$($gen:tt)*] $vis:vis struct $Name:ident<isize> { $($body:tt)* }) => {
+        $crate::bitfield! { @generate [$($attrs)*] [$($opts)*] [$($gen)*] $vis struct $Name<usize as isize> { $($body)* } }
+    };
+    (@process_attrs [$($attrs:tt)*] [$($opts:tt)*] [$($gen:tt)*] $vis:vis struct $Name:ident<$T:ident> { $($body:tt)* }) => {
+        $crate::bitfield! { @generate [$($attrs)*] [$($opts)*] [$($gen)*] $vis struct $Name<$T as $T> { $($body)* } }
+    };
+    // `$T` is the unsigned integer type that stores the bitfield's bits, and
+    // `$Repr` is the integer type the bitfield was declared with.
+    (@generate [$(#[$meta:meta])*] [$($opts:tt)*] [$(const $G:ident: $GT:ty),*]
+        $vis:vis struct $Name:ident<$T:ident as $Repr:ident> {
+            $(
+                $(#[$($field_attr:tt)*])*
+                $field_vis:vis const $Field:ident $(@ $offset:literal)? $(: $F:ty)? $( = $val:tt $(must_be($must:expr))?)? $(=> $note:literal)?;
+            )+
+        }
+    ) => {
+        $crate::bitfield! { @validate_opts $($opts)* }
+        $(
+            $crate::bitfield! { @validate_width $Field $( = $val)? }
+        )+
+
+        $(#[$meta])*
+        #[derive(Copy, Clone)]
+        #[repr(transparent)]
+        $vis struct $Name<$(const $G: $GT),*>($T);
+
+        // Evaluate each packing spec at compile time, so that fields which
+        // do not fit in the bitfield's integer type are a compile error, and
+        // check that the bitfield's layout is valid.
+        $crate::bitfield! { @const_validate [$(const $G: $GT),*] {
+            $(
+                let _ = $Name::$Field;
+            )+
+            $Name::assert_valid_const();
+            $($($(
+                assert!(
+                    ($must) <= $Name::$Field.max_value(),
+                    concat!("the required value of field `", stringify!($Field), "` does not fit in the field"),
+                );
+            )?)?)+
+        } }
+
+        $crate::bitfield! { @unless_no_debug [$($opts)*] {
+            #[automatically_derived]
+            impl<$(const $G: $GT),*> core::fmt::Debug for $Name<$($G),*> {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    let mut dbg = f.debug_struct(stringify!($Name));
+                    $(
+                        // Reserved fields are not included in the output.
+                        if !stringify!($Field).starts_with('_') {
+                            dbg.field(stringify!($Field), &self.field_value(Self::$Field));
+                        }
+                    )+
+                    dbg.finish()
+
+                }
+            }
+        } }
+
+        // Some generated methods may not always be used, which may emit dead
+        // code warnings if the type is private.
+        #[allow(dead_code)]
+        #[automatically_derived]
+        impl<$(const $G: $GT),*> $Name<$($G),*> {
+            $crate::bitfield! { @field<$T>:
+                $(
+                    $(#[$($field_attr)*])*
+                    $field_vis const $Field $(@ $offset)? $(: $F)? $( = $val)?;
+                )+
+            }
+
+            const FIELDS: &'static [(&'static str, $crate::bitfield! { @t $T, $T, Self })] = &[$(
+                (stringify!($Field), Self::$Field.typed())
+            ),+];
+
+            const FIELD_NOTES: &'static [&'static str] = &[$(
+                $crate::bitfield! { @field_note $($note)? }
+            ),+];
+
+            const FIELD_DOCS: &'static [(&'static str, &'static str)] = &[$(
+                (stringify!($Field), $crate::bitfield! { @field_docs [] $(#[$($field_attr)*])* })
+            ),+];
+
+            /// Returns the name and doc comment of each of this type's
+            /// fields, including reserved fields, in order from least- to
+            /// most-significant.
+            ///
+            /// Multi-line doc comments are joined with newlines. Each line
+            /// is included as written, including the leading space after
+            /// `///`. Fields without doc comments have an empty string as
+            /// their documentation.
+            $vis const fn field_docs() -> &'static [(&'static str, &'static str)] {
+                Self::FIELD_DOCS
+            }
+
+            /// Constructs a new instance of `Self` from the provided raw bits.
+            $vis const fn from_bits(bits: $Repr) -> Self {
+                Self(bits as $T)
+            }
+
+            /// Constructs a new instance of `Self` from the provided raw bits,
+            /// clearing any bits which are not covered by one of this type's
+            /// fields.
+            ///
+            /// Reserved fields (whose names begin with `_`) are considered
+            /// declared fields, so their bits are *not* cleared.
+            $vis const fn from_bits_truncate(bits: $Repr) -> Self {
+                let mut mask = 0;
+                let mut i = 0;
+                while i < Self::FIELDS.len() {
+                    mask |= Self::FIELDS[i].1.raw_mask();
+                    i += 1;
+                }
+                Self(bits as $T & mask)
+            }
+
+            $crate::bitfield! { @bits_accessor [$($opts)*] $vis $Repr }
+
+            /// Constructs a new instance of `Self` with all bits set to 0,
+            /// except for fields declared with a required value
+            /// (`must_be(VALUE)`), which are set to that value.
+            $vis const fn new() -> Self {
+                Self(0 $($($(| Self::$Field.pack_truncating($must, 0))?)?)+)
+            }
+
+            /// Packs the bit representation of `value` into `self` at the bit
+            /// range designated by `field`, returning a new bitfield.
             $vis fn with<T>(self, field: $crate::bitfield! { @t $T, T, Self }, value: T) -> Self
             where
                 T: $crate::FromBits<$T>,
@@ -326,6 +1192,81 @@ macro_rules! bitfield {
                 Self(field.pack(value, self.0))
             }
 
+            /// Packs the bit representation of `value` into `self` at the bit
+            /// range designated by `field`, returning a new bitfield, or an
+            /// error if `value` does not fit in `field`.
+            ///
+            /// Unlike [`with`](Self::with), this method never panics or
+            /// discards bits if `value` is out of range.
+            ///
+            /// # Returns
+            ///
+            /// - `Ok(Self)` if the bit representation of `value` fits in the
+            ///   bit range designated by `field`.
+            /// - `Err(`[`OutOfRange`]`)` if `value` has bits set outside of the
+            ///   range designated by `field`.
+            ///
+            /// [`OutOfRange`]: $crate::OutOfRange
+            $vis fn checked_with<T>(self, field: $crate::bitfield! { @t $T, T, Self }, value: T) -> Result<Self, $crate::OutOfRange>
+            where
+                T: $crate::FromBits<$T>,
+            {
+                let value = value.into_bits();
+                if value > field.max_value() {
+                    let value_bits = <$T>::BITS - value.leading_zeros();
+                    return Err($crate::OutOfRange::new(field.bits(), value_bits));
+                }
+                Ok(Self(field.pack_truncating(value, self.0)))
+            }
+
+            /// Packs the bit representation of `value` into `self` at the bit
+            /// range designated by `field`, returning a new bitfield. If
+            /// `value` is too large to fit in `field`, the field is set to its
+            /// maximum value (all ones) instead.
+            ///
+            /// Unlike [`with`](Self::with), this method never panics or
+            /// discards bits if `value` is out of range.
+            ///
+            /// Saturation is performed on the unsigned bit representation
+            /// returned by [`FromBits::into_bits`]. Typed fields are always
+            /// exactly as wide as their type's [`FromBits::BITS`], so a typed
+            /// value is never clamped; in particular, signed integer fields
+            /// store negative values in two's complement, rather than
+            /// saturating them to zero or the field's maximum. In practice,
+            /// this method is useful for raw integer fields.
+            ///
+            /// [`FromBits::into_bits`]: $crate::FromBits::into_bits
+            /// [`FromBits::BITS`]: $crate::FromBits::BITS
+            $vis fn with_saturating<T>(self, field: $crate::bitfield! { @t $T, T, Self }, value: T) -> Self
+            where
+                T: $crate::FromBits<$T>,
+            {
+                let value = value.into_bits();
+                let max = field.max_value();
+                let value = if value > max { max } else { value };
+                Self(field.pack_truncating(value, self.0))
+            }
+
+            /// Packs the raw bits in `value` into `self` at the bit range
+            /// designated by `field`, returning a new bitfield.
+            ///
+            /// Unlike [`with`](Self::with), this is a `const fn`, so it can be
+            /// used to construct bitfield values in `const` and `static`
+            /// initializers. Because [`FromBits::into_bits`] cannot be called
+            /// in a `const fn`, this method only accepts fields that pack raw
+            /// integer values (i.e. fields declared as `const FIELD = N;`).
+            ///
+            /// # Panics
+            ///
+            /// If `value` has bits set outside of the range designated by
+            /// `field`. When this method is called in a const context, this
+            /// is a compile-time error.
+            ///
+            /// [`FromBits::into_bits`]: $crate::FromBits::into_bits
+            $vis const fn with_const(self, field: $crate::bitfield! { @t $T, $T, Self }, value: $T) -> Self {
+                assert!(value <= field.max_value(), "bits outside of packed range are set!");
+                Self(field.pack_truncating(value, self.0))
+            }
 
             /// Packs the bit representation of `value` into `self` at the range
             /// designated by `field`, mutating `self` in place.
@@ -337,6 +1278,99 @@ macro_rules! bitfield {
                 self
             }
 
+            /// Packs the bit representation of `value` into `self` at the range
+            /// designated by `field`, returning the value previously stored in
+            /// that field.
+            ///
+            /// This is similar to [`Cell::replace`](core::cell::Cell::replace),
+            /// and is equivalent to calling [`get`](Self::get) followed by
+            /// [`set`](Self::set).
+            ///
+            /// # Panics
+            ///
+            /// This method panics if `field` does not contain a valid bit
+            /// pattern for a `T`-typed value, as determined by `T`'s
+            /// `FromBits::try_from_bits` implementation. In that case, `self`
+            /// is not modified.
+            $vis fn replace<T>(&mut self, field: $crate::bitfield! { @t $T, T, Self }, value: T) -> T
+            where
+                T: $crate::FromBits<$T>,
+            {
+                let prev = self.get(field);
+                self.set(field, value);
+                prev
+            }
+
+            /// Adds 1 to the value of the raw integer field `field`, wrapping
+            /// around to 0 if the field is already at its maximum value.
+            ///
+            /// Overflow wraps within the width of `field`, so incrementing a
+            /// field never changes the value of any other field.
+            $vis fn wrapping_increment(&mut self, field: $crate::bitfield! { @t $T, $T, Self }) -> &mut Self {
+                let value = field.unpack_bits(self.0);
+                let value = if value == field.max_value() { 0 } else { value + 1 };
+                self.0 = field.pack_truncating(value, self.0);
+                self
+            }
+
+            /// Adds 1 to the value of the raw integer field `field`.
+            ///
+            /// # Returns
+            ///
+            /// - `Some(&mut Self)` if the field was incremented.
+            /// - `None` if the field is already at its maximum value. In
+            ///   this case, `self` is not modified.
+            $vis fn checked_increment(&mut self, field: $crate::bitfield! { @t $T, $T, Self }) -> Option<&mut Self> {
+                let value = field.unpack_bits(self.0);
+                if value == field.max_value() {
+                    return None;
+                }
+                self.0 = field.pack_truncating(value + 1, self.0);
+                Some(self)
+            }
+
+            /// Subtracts 1 from the value of the raw integer field `field`,
+            /// wrapping around to the field's maximum value if the field is
+            /// 0.
+            ///
+            /// Underflow wraps within the width of `field`, so decrementing a
+            /// field never changes the value of any other field.
+            $vis fn wrapping_decrement(&mut self, field: $crate::bitfield! { @t $T, $T, Self }) -> &mut Self {
+                let value = field.unpack_bits(self.0);
+                let value = if value == 0 { field.max_value() } else { value - 1 };
+                self.0 = field.pack_truncating(value, self.0);
+                self
+            }
+
+            /// Subtracts 1 from the value of the raw integer field `field`.
+            ///
+            /// # Returns
+            ///
+            /// - `Some(&mut Self)` if the field was decremented.
+            /// - `None` if the field is already 0. In this case, `self` is
+            ///   not modified.
+            $vis fn checked_decrement(&mut self, field: $crate::bitfield! { @t $T, $T, Self }) -> Option<&mut Self> {
+                let value = field.unpack_bits(self.0);
+                if value == 0 {
+                    return None;
+                }
+                self.0 = field.pack_truncating(value - 1, self.0);
+                Some(self)
+            }
+
+            /// Constructs a new instance of `Self` using [`new`](Self::new),
+            /// and then calls `f` with a mutable reference to it, returning
+            /// the result.
+            ///
+            /// This allows a bitfield's fields to be set in a single block,
+            /// using [`set`](Self::set) and related methods, rather than a
+            /// long chain of [`with`](Self::with) calls.
+            $vis fn build(f: impl FnOnce(&mut Self)) -> Self {
+                let mut this = Self::new();
+                f(&mut this);
+                this
+            }
+
             /// Unpacks the bit range represented by `field` from `self`, and
             /// converts it into a `T`-typed value.
             ///
@@ -345,7 +1379,7 @@ macro_rules! bitfield {
             /// This method panics if `self` does not contain a valid bit
             /// pattern for a `T`-typed value, as determined by `T`'s
             /// `FromBits::try_from_bits` implementation.
-            $vis fn get<T>(self, field: $crate::bitfield! { @t $T, T, Self }) -> T
+            $vis fn get<T>(&self, field: $crate::bitfield! { @t $T, T, Self }) -> T
             where
                 T: $crate::FromBits<$T>,
             {
@@ -362,147 +1396,727 @@ macro_rules! bitfield {
             /// - `Err(T::Error)` if `src` does not contain a valid bit
             ///   pattern for a `T`-typed value, as determined by `T`'s
             ///   [`FromBits::try_from_bits` implementation.
-            $vis fn try_get<T>(self, field: $crate::bitfield! { @t $T, T, Self }) -> Result<T, T::Error>
+            $vis fn try_get<T>(&self, field: $crate::bitfield! { @t $T, T, Self }) -> Result<T, T::Error>
             where
                 T: $crate::FromBits<$T>,
             {
                 field.try_unpack(self.0)
             }
 
-            /// Asserts that all the packing specs for this type are valid.
+            /// Unpacks the bit range represented by `field` from `self`, and
+            /// converts it into a `T`-typed value, returning `None` if the bits
+            /// are not a valid bit pattern for a `T`.
             ///
-            /// This is intended to be used in unit tests.
-            $vis fn assert_valid() {
-                <$crate::bitfield! { @t $T, $T, Self }>::assert_all_valid(&Self::FIELDS);
+            /// This is equivalent to `self.try_get(field).ok()`, and is
+            /// provided as a convenience for use in `Option` combinators and
+            /// iterator chains.
+            $vis fn get_opt<T>(&self, field: $crate::bitfield! { @t $T, T, Self }) -> Option<T>
+            where
+                T: $crate::FromBits<$T>,
+            {
+                field.try_unpack(self.0).ok()
             }
-        }
 
-        #[automatically_derived]
-        impl core::fmt::Display for $Name {
-            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-                f.pad("")?;
-                writeln!(f, "{:0width$b}", self.0, width = $T::BITS as usize)?;
-                f.pad("")?;
-                let mut cur_pos = $T::BITS;
-                let mut max_len = 0;
-                let mut rem = 0;
-                let mut fields = Self::FIELDS.iter().rev().peekable();
-                while let Some((name, field)) = fields.next() {
-                    while cur_pos > field.most_significant_index() {
-                        f.write_str(" ")?;
-                        cur_pos -= 1;
-                    }
-                    let bits = field.bits();
-                    match (name, bits) {
-                        (name, bits) if name.starts_with("_") => {
-                            for _ in 0..bits {
-                                f.write_str(" ")?;
-                            }
-                            cur_pos -= bits;
-                            continue;
-                        }
-                        (_, 1) => f.write_str("│")?,
-                        (_, 2) => f.write_str("└┤")?,
-                        (_, bits) => {
-                            f.write_str("└┬")?;
-                            for _ in 0..(bits - 3) {
-                                f.write_str("─")?;
-                            }
-                            f.write_str("┘")?;
-                        }
-                    }
+            /// Returns a value which formats `field`'s value in `self` using
+            /// its `Debug` implementation, or `field`'s raw bits if they are
+            /// not a valid bit pattern for a `T`.
+            fn field_value<T>(&self, field: $crate::bitfield! { @t $T, T, Self }) -> $crate::__FieldValue<T>
+            where
+                T: $crate::FromBits<$T>,
+            {
+                $crate::__FieldValue::new(field.try_unpack(self.0).ok(), field.unpack_bits(self.0) as u128)
+            }
 
-                    if fields.peek().is_none() {
-                        rem = cur_pos - (bits - 1);
-                    }
+            /// Returns the raw bits in the range of `len` bits starting at bit
+            /// `lsb` (counting from the least-significant bit), shifted down
+            /// so that bit `lsb` is the least-significant bit of the result.
+            ///
+            /// Unlike [`get`](Self::get), the bit range is computed at
+            /// runtime, rather than by a packing spec, so this may be used to
+            /// access fields whose position is not known at compile time.
+            ///
+            /// # Panics
+            ///
+            /// If `lsb + len` is greater than the number of bits in the
+            /// bitfield's integer type.
+            #[track_caller]
+            $vis fn get_range(&self, lsb: u32, len: u32) -> $T {
+                let max = Self::range_max(lsb, len);
+                self.0.checked_shr(lsb).unwrap_or(0) & max
+            }
 
-                    max_len = core::cmp::max(max_len, name.len());
-                    cur_pos -= field.bits()
-                }
+            /// Packs the raw bits in `value` into the range of `len` bits
+            /// starting at bit `lsb` (counting from the least-significant
+            /// bit), mutating `self` in place.
+            ///
+            /// This is the runtime counterpart of [`set`](Self::set); see
+            /// [`get_range`](Self::get_range) for details.
+            ///
+            /// # Panics
+            ///
+            /// - If `lsb + len` is greater than the number of bits in the
+            ///   bitfield's integer type.
+            /// - If `value` has bits set outside of the `len` least-significant
+            ///   bits.
+            #[track_caller]
+            $vis fn set_range(&mut self, lsb: u32, len: u32, value: $T) -> &mut Self {
+                let max = Self::range_max(lsb, len);
+                assert!(
+                    value <= max,
+                    "value {:#x} does not fit in {} bits",
+                    value,
+                    len,
+                );
+                let mask = max.checked_shl(lsb).unwrap_or(0);
+                self.0 = (self.0 & !mask) | value.checked_shl(lsb).unwrap_or(0);
+                self
+            }
 
-                f.write_str("\n")?;
+            /// Returns `true` if bit `n` (counting from the least-significant
+            /// bit) is set, regardless of which field contains it.
+            ///
+            /// This is useful for probing registers whose fields have not yet
+            /// been declared.
+            ///
+            /// # Panics
+            ///
+            /// If `n` is greater than or equal to the number of bits in the
+            /// bitfield's integer type.
+            #[track_caller]
+            $vis fn bit(&self, n: u32) -> bool {
+                self.get_range(n, 1) != 0
+            }
 
-                $(
-                    let field = Self::$Field;
-                    let name = stringify!($Field);
-                    if !name.starts_with("_") {
-                        f.pad("")?;
-                        cur_pos = $T::BITS;
-                        for (cur_name, cur_field) in Self::FIELDS.iter().rev() {
-                            while cur_pos > cur_field.most_significant_index() {
-                                f.write_str(" ")?;
-                                cur_pos -= 1;
-                            }
-
-                            if field == cur_field {
-                                break;
-                            }
-
-                            let bits = cur_field.bits();
-                            match (cur_name, bits) {
-                                (name, bits) if name.starts_with("_") => {
-                                    for _ in 0..bits {
-                                        f.write_str(" ")?;
-                                    }
-                                }
-                                (_, 1) => f.write_str("│")?,
-                                (_, bits) => {
-                                    f.write_str(" │")?;
-                                    for _ in 0..(bits - 2) {
-                                        f.write_str(" ")?;
-                                    }
-                                }
-                            }
-
-                            cur_pos -= bits;
-                        }
+            /// Sets bit `n` (counting from the least-significant bit) to
+            /// `value`, regardless of which field contains it, mutating `self`
+            /// in place.
+            ///
+            /// # Panics
+            ///
+            /// If `n` is greater than or equal to the number of bits in the
+            /// bitfield's integer type.
+            #[track_caller]
+            $vis fn set_bit(&mut self, n: u32, value: bool) -> &mut Self {
+                self.set_range(n, 1, value as $T)
+            }
 
-                        let field_bits = field.bits();
-                        if field_bits == 1 {
-                            f.write_str("└")?;
-                            cur_pos -= 1;
-                        } else {
-                            f.write_str(" └")?;
-                            cur_pos -= 2;
-                        }
-                        let len = cur_pos as usize + (max_len - name.len());
-                        for _ in rem as usize..len {
-                            f.write_str("─")?;
-                        }
-                        writeln!(f, " {}: {:?} ({:0width$b})", name, field.unpack(self.0), field.unpack_bits(self.0), width = field_bits as usize)?
-                    }
+            /// Returns the maximum value of a `len`-bit range starting at bit
+            /// `lsb`, asserting that the range fits in the bitfield's integer type.
+            #[inline]
+            #[track_caller]
+            fn range_max(lsb: u32, len: u32) -> $T {
+                assert!(
+                    lsb <= <$T>::BITS && len <= <$T>::BITS - lsb,
+                    "bit range {}..{} is out of range for a `{}`",
+                    lsb,
+                    lsb.saturating_add(len),
+                    stringify!($T),
+                );
+                if len == 0 {
+                    return 0;
+                }
+                <$T>::MAX >> (<$T>::BITS - len)
+            }
 
-                )+
+            /// Returns `true` if `self` is equal to [`Self::new()`], i.e. if
+            /// no bits are set other than the required values of fields
+            /// declared with `must_be`.
+            ///
+            /// For bitfields without required values, this returns `true` if
+            /// all bits are 0.
+            $vis const fn is_empty(&self) -> bool {
+                self.0 == Self::new().0
+            }
 
-                Ok(())
+            /// Returns `true` if any bits are set in `self` other than the
+            /// required values of fields declared with `must_be`.
+            ///
+            /// This is the inverse of [`is_empty`](Self::is_empty).
+            $vis const fn any(&self) -> bool {
+                !self.is_empty()
             }
-        }
 
-        #[automatically_derived]
-        impl core::fmt::Binary for $Name {
-            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-                if f.alternate() {
-                    f.debug_tuple(stringify!($Name)).field(&format_args!("{:#b}", self)).finish()
-                } else {
-                    f.debug_tuple(stringify!($Name)).field(&format_args!("{:b}", self)).finish()
-                }
+            /// Returns the number of ones in the raw bits of `self`.
+            ///
+            /// This counts every set bit, regardless of field declarations,
+            /// and is equivalent to `self.bits().count_ones()`.
+            #[inline]
+            $vis const fn count_ones(&self) -> u32 {
+                self.0.count_ones()
+            }
+
+            /// Returns the number of zeros in the raw bits of `self`.
+            ///
+            /// This counts every unset bit, regardless of field declarations,
+            /// and is equivalent to `self.bits().count_zeros()`.
+            #[inline]
+            $vis const fn count_zeros(&self) -> u32 {
+                self.0.count_zeros()
+            }
+
+            /// Returns `true` if the single-bit flag represented by `flag` is
+            /// set in `self`.
+            ///
+            /// This is equivalent to `self.get(flag)`.
+            $vis fn contains(&self, flag: $crate::bitfield! { @t $T, bool, Self }) -> bool {
+                flag.unpack(self.0)
+            }
+
+            /// Sets the single-bit flag represented by `flag` in `self`.
+            ///
+            /// This is equivalent to `self.set(flag, true)`.
+            $vis fn insert(&mut self, flag: $crate::bitfield! { @t $T, bool, Self }) -> &mut Self {
+                self.set(flag, true)
+            }
+
+            /// Clears the single-bit flag represented by `flag` in `self`.
+            ///
+            /// This is equivalent to `self.set(flag, false)`.
+            $vis fn remove(&mut self, flag: $crate::bitfield! { @t $T, bool, Self }) -> &mut Self {
+                self.set(flag, false)
+            }
+
+            /// Inverts the single-bit flag represented by `flag` in `self`.
+            $vis fn toggle(&mut self, flag: $crate::bitfield! { @t $T, bool, Self }) -> &mut Self {
+                debug_assert_eq!(flag.bits(), 1, "only single-bit flags may be toggled");
+                self.0 ^= flag.raw_mask();
+                self
+            }
+
+            /// Returns an iterator over the index of each bit which is set in
+            /// `self`'s raw bits.
+            ///
+            /// Indices are yielded in order from least- to most-significant.
+            /// This does not depend on the bitfield's declared fields, so it
+            /// may be used to inspect, e.g., a bitmap of pending interrupts.
+            $vis fn set_bit_indices(&self) -> impl Iterator<Item = u32> {
+                let mut bits = self.0;
+                core::iter::from_fn(move || {
+                    if bits == 0 {
+                        return None;
+                    }
+                    let idx = bits.trailing_zeros();
+                    // clear the least-significant set bit.
+                    bits &= bits - 1;
+                    Some(idx)
+                })
+            }
+
+            /// Calls `f` with the name and unpacked value of each field in
+            /// `self`.
+            ///
+            /// Fields are visited in order from least- to most-significant.
+            /// Reserved fields (whose names begin with `_`) are skipped. Unlike
+            /// [`fields`](Self::fields), typed fields are passed to `f` as
+            /// their typed values, so their [`Debug`](core::fmt::Debug)
+            /// implementations are used. This is useful for recording each
+            /// field as a separate key-value pair in structured logging.
+            ///
+            /// If a typed field does not contain a valid bit pattern for its
+            /// type, its raw bits are passed to `f` instead, formatted as
+            /// `Err(0b...)`, as in the bitfield's `Debug` output.
+            $vis fn record_fields(&self, mut f: impl FnMut(&'static str, &dyn core::fmt::Debug)) {
+                $(
+                    let name = stringify!($Field);
+                    if !name.starts_with('_') {
+                        f(name, &self.field_value(Self::$Field));
+                    }
+                )+
+            }
+
+            /// Returns an iterator over the name and raw bits of each field
+            /// in `self`.
+            ///
+            /// Fields are yielded in order from least- to most-significant.
+            /// Reserved fields (whose names begin with `_`) are skipped.
+            $vis fn fields(&self) -> impl Iterator<Item = (&'static str, $T)> {
+                let bits = self.0;
+                Self::FIELDS
+                    .iter()
+                    .filter(|(name, _)| !name.starts_with('_'))
+                    .map(move |(name, field)| (*name, field.unpack_bits(bits)))
+            }
+
+            /// Returns an iterator over each field whose value differs between
+            /// `self` and `other`.
+            ///
+            /// Each item is the field's name, its raw bits in `self`, and its
+            /// raw bits in `other`. Fields are yielded in order from least- to
+            /// most-significant. Reserved fields (whose names begin with `_`)
+            /// are skipped, even if their bits differ. This is useful for
+            /// logging state transitions of a register, without printing both
+            /// of its values in full.
+            $vis fn diff(&self, other: &Self) -> impl Iterator<Item = (&'static str, $T, $T)> {
+                let (old, new) = (self.0, other.0);
+                Self::FIELDS
+                    .iter()
+                    .filter(|(name, _)| !name.starts_with('_'))
+                    .filter_map(move |(name, field)| {
+                        let (old, new) = (field.unpack_bits(old), field.unpack_bits(new));
+                        if old == new {
+                            return None;
+                        }
+                        Some((*name, old, new))
+                    })
+            }
+
+            /// Asserts that all the packing specs for this type are valid.
+            ///
+            /// This also asserts that the packing spec for each typed field is
+            /// exactly as wide as that type's [`FromBits::BITS`].
+            ///
+            /// This is intended to be used in unit tests.
+            ///
+            /// [`FromBits::BITS`]: $crate::FromBits::BITS
+            $vis fn assert_valid() {
+                <$crate::bitfield! { @t $T, $T, Self }>::assert_all_valid(&Self::FIELDS);
+                $(
+                    $crate::bitfield! { @assert_valid_typed Self::$Field $(: $F)? }
+                )+
+            }
+
+            /// Asserts that the layout of this type's packing specs is valid,
+            /// in a `const` context.
+            ///
+            /// This asserts that every packing spec fits within the bitfield's
+            /// integer type, and that no two packing specs overlap. Unlike
+            /// [`assert_valid`](Self::assert_valid), the panic messages
+            /// emitted by this function do not describe which packing specs
+            /// are invalid.
+            ///
+            /// The `bitfield!` macro calls this function in a `const` item, so
+            /// an invalid layout is always a compile-time error; it is not
+            /// necessary to call it manually.
+            $vis const fn assert_valid_const() {
+                let mut i = 0;
+                while i < Self::FIELDS.len() {
+                    let field = &Self::FIELDS[i].1;
+                    assert!(
+                        field.least_significant_index() < <$T>::BITS,
+                        concat!("a field of bitfield `", stringify!($Name), "` starts beyond the last bit of a `", stringify!($T), "`"),
+                    );
+                    assert!(
+                        field.bits() <= <$T>::BITS - field.least_significant_index(),
+                        concat!("a field of bitfield `", stringify!($Name), "` does not fit in a `", stringify!($T), "`"),
+                    );
+                    let mut j = i + 1;
+                    while j < Self::FIELDS.len() {
+                        assert!(
+                            field.raw_mask() & Self::FIELDS[j].1.raw_mask() == 0,
+                            concat!("fields of bitfield `", stringify!($Name), "` overlap"),
+                        );
+                        j += 1;
+                    }
+                    i += 1;
+                }
+                $crate::bitfield! { @exhaustive [$($opts)*] $Name, $T }
+            }
+        }
+
+        $crate::bitfield! { @unless_no_display [$($opts)*] {
+            #[automatically_derived]
+            impl<$(const $G: $GT),*> core::fmt::Display for $Name<$($G),*> {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    let diagram = $crate::__Diagram {
+                        name: stringify!($Name),
+                        bits: self.0 as u128,
+                        width: $T::BITS,
+                        names: &[$(stringify!($Field)),+],
+                        masks: &[$(Self::$Field.raw_mask() as u128),+],
+                        notes: Self::FIELD_NOTES,
+                        values: &[$(&self.field_value(Self::$Field)),+],
+                    };
+                    core::fmt::Display::fmt(&diagram, f)
+                }
+            }
+        } }
+
+        $crate::bitfield! { @impl_frombits [$(const $G: $GT),*] $Name<$($G),*>, $T }
+
+        #[automatically_derived]
+        impl<$(const $G: $GT),*> $crate::BitfieldExt for $Name<$($G),*> {
+            type Repr = $Repr;
+
+            #[inline]
+            fn bits(&self) -> $Repr {
+                self.0 as $Repr
+            }
+
+            #[inline]
+            fn from_bits(bits: $Repr) -> Self {
+                Self::from_bits(bits)
+            }
+        }
+
+        #[automatically_derived]
+        impl<$(const $G: $GT),*> core::convert::TryFrom<$Repr> for $Name<$($G),*> {
+            type Error = $crate::InvalidField;
+
+            fn try_from(bits: $Repr) -> Result<Self, Self::Error> {
+                let this = Self::from_bits(bits);
+                $(
+                    if this.try_get(Self::$Field).is_err() {
+                        return Err($crate::InvalidField::new(stringify!($Field)));
+                    }
+                )+
+                Ok(this)
+            }
+        }
+
+        #[automatically_derived]
+        impl<$(const $G: $GT),*> core::convert::From<$Name<$($G),*>> for $Repr {
+            #[inline]
+            fn from(bitfield: $Name<$($G),*>) -> Self {
+                bitfield.0 as $Repr
+            }
+        }
+
+        $crate::__bitfield_impl_serde! { [$(const $G: $GT),*] $Name<$($G),*>, $Repr }
+
+        $crate::__bitfield_impl_arbitrary! { [$(const $G: $GT),*] $Name<$($G),*>, $Repr }
+
+        $crate::__bitfield_impl_tracing! { [$(const $G: $GT),*] $vis $Name<$($G),*> }
+
+        $crate::bitfield! { @partial_eq_bits [$($opts)*] [$(const $G: $GT),*] $Name<$($G),*>, $Repr }
+
+        $crate::bitfield! { @bitwise_ops [$($opts)*] [$(const $G: $GT),*] $Name<$($G),*> }
+
+        $crate::bitfield! { @default [$($opts)*] [$(const $G: $GT),*] $Name<$($G),*> }
+
+        $crate::bitfield! {
+            @decode [$($opts)*] [$(const $G: $GT),*] $vis $Name<$T> {
+                $( [$(#[$($field_attr)*])*] $field_vis $Field $(: $F)? ),+
+            }
+        }
+
+        $crate::bitfield! {
+            @masks [$($opts)*] [$(const $G: $GT),*] $vis $Name, $Name<$($G),*> {
+                $( [$(#[$($field_attr)*])*] $field_vis $Field ),+
+            }
+        }
+
+        #[automatically_derived]
+        impl<$(const $G: $GT),*> core::fmt::Binary for $Name<$($G),*> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                if f.alternate() {
+                    f.debug_tuple(stringify!($Name)).field(&format_args!("{:#b}", self.0)).finish()
+                } else {
+                    f.debug_tuple(stringify!($Name)).field(&format_args!("{:b}", self.0)).finish()
+                }
+            }
+        }
+    };
+    // Implement `FromBits` for the bitfield type, for its own integer type and
+    // every wider unsigned integer type, so that it may be nested in other
+    // bitfields.
+    (@impl_frombits [$($gen:tt)*] $Name:ty, u8) => {
+        $crate::bitfield! { @impl_frombits [$($gen)*] $Name, u8; for u8, u16, u32, u64, u128, usize }
+    };
+    (@impl_frombits [$($gen:tt)*] $Name:ty, u16) => {
+        $crate::bitfield! { @impl_frombits [$($gen)*] $Name, u16; for u16, u32, u64, u128, usize }
+    };
+    (@impl_frombits [$($gen:tt)*] $Name:ty, u32) => {
+        $crate::bitfield! { @impl_frombits [$($gen)*] $Name, u32; for u32, u64, u128 }
+        #[cfg(any(target_pointer_width = "32", target_pointer_width = "64"))]
+        $crate::bitfield! { @impl_frombits [$($gen)*] $Name, u32; for usize }
+    };
+    (@impl_frombits [$($gen:tt)*] $Name:ty, u64) => {
+        $crate::bitfield! { @impl_frombits [$($gen)*] $Name, u64; for u64, u128 }
+        #[cfg(target_pointer_width = "64")]
+        $crate::bitfield! { @impl_frombits [$($gen)*] $Name, u64; for usize }
+    };
+    (@impl_frombits [$($gen:tt)*] $Name:ty, u128) => {
+        $crate::bitfield! { @impl_frombits [$($gen)*] $Name, u128; for u128 }
+    };
+    (@impl_frombits [$($gen:tt)*] $Name:ty, usize) => {
+        $crate::bitfield! { @impl_frombits [$($gen)*] $Name, usize; for usize, u128 }
+    };
+    (@impl_frombits [$($gen:tt)*] $Name:ty, $T:ident; for $F:ident $(, $rest:ident)*) => {
+        #[automatically_derived]
+        impl<$($gen)*> $crate::FromBits<$F> for $Name {
+            const BITS: u32 = <$T>::BITS;
+            type Error = core::convert::Infallible;
+
+            #[inline]
+            fn try_from_bits(bits: $F) -> Result<Self, Self::Error> {
+                Ok(Self(bits as $T))
+            }
+
+            #[inline]
+            fn into_bits(self) -> $F {
+                self.0 as $F
+            }
+        }
+
+        $crate::bitfield! { @impl_frombits [$($gen)*] $Name, $T; for $($rest),* }
+    };
+    (@impl_frombits [$($gen:tt)*] $Name:ty, $T:ident; for) => {};
+
+    // Generic bitfields cannot be evaluated until their parameters are
+    // known, so their layouts are only validated when their packing specs
+    // are used, or by `assert_valid`.
+    (@const_validate [] { $($body:tt)* }) => {
+        // A required value of 0 always fits, so comparing it against the
+        // field's maximum value would otherwise warn.
+        #[allow(unused_comparisons)]
+        const _: () = { $($body)* };
+    };
+    (@const_validate [$($gen:tt)+] { $($body:tt)* }) => {};
+
+    (@assert_valid_typed Self::$Field:ident: $F:ty) => {
+        Self::$Field.assert_valid_typed();
+    };
+    (@assert_valid_typed Self::$Field:ident) => {};
+
+    (@validate_width $Field:ident = 0) => {
+        compile_error!(concat!("field `", stringify!($Field), "` must be at least 1 bit wide"));
+    };
+    (@validate_width $Field:ident $($rest:tt)*) => {};
+
+    // Concatenate the doc comments in a list of field attributes, separated
+    // by newlines. Any other attributes are ignored.
+    (@field_docs [$($doc:literal)*] #[doc = $line:literal] $($rest:tt)*) => {
+        $crate::bitfield! { @field_docs [$($doc)* $line] $($rest)* }
+    };
+    (@field_docs [$($doc:literal)*] #[$($attr:tt)*] $($rest:tt)*) => {
+        $crate::bitfield! { @field_docs [$($doc)*] $($rest)* }
+    };
+    (@field_docs []) => { "" };
+
+    (@field_note) => { "" };
+    (@field_note $note:literal) => { $note };
+    (@field_docs [$first:literal $($doc:literal)*]) => {
+        concat!($first $(, "\n", $doc)*)
+    };
+
+    (@validate_opts) => {};
+    (@validate_opts , $($rest:tt)*) => { $crate::bitfield! { @validate_opts $($rest)* } };
+    (@validate_opts partial_eq_bits $($rest:tt)*) => { $crate::bitfield! { @validate_opts $($rest)* } };
+    (@validate_opts bitwise_ops $($rest:tt)*) => { $crate::bitfield! { @validate_opts $($rest)* } };
+    (@validate_opts default($($Field:ident = $val:expr),* $(,)?) $($rest:tt)*) => { $crate::bitfield! { @validate_opts $($rest)* } };
+    (@validate_opts decode($Fields:ident) $($rest:tt)*) => { $crate::bitfield! { @validate_opts $($rest)* } };
+    (@validate_opts bits_accessor($bits:ident) $($rest:tt)*) => { $crate::bitfield! { @validate_opts $($rest)* } };
+    (@validate_opts no_debug $($rest:tt)*) => { $crate::bitfield! { @validate_opts $($rest)* } };
+    (@validate_opts no_display $($rest:tt)*) => { $crate::bitfield! { @validate_opts $($rest)* } };
+    (@validate_opts exhaustive $($rest:tt)*) => { $crate::bitfield! { @validate_opts $($rest)* } };
+    (@validate_opts masks($Masks:ident) $($rest:tt)*) => { $crate::bitfield! { @validate_opts $($rest)* } };
+    (@validate_opts $opt:tt $($rest:tt)*) => {
+        compile_error!(concat!("unknown bitfield option `", stringify!($opt), "`"));
+    };
+
+    (@partial_eq_bits [] [$($gen:tt)*] $Name:ty, $T:ident) => {};
+    (@partial_eq_bits [partial_eq_bits $($rest:tt)*] [$($gen:tt)*] $Name:ty, $T:ident) => {
+        #[automatically_derived]
+        impl<$($gen)*> core::cmp::PartialEq<$T> for $Name {
+            #[inline]
+            fn eq(&self, other: &$T) -> bool {
+                self.0 as $T == *other
+            }
+        }
+
+        #[automatically_derived]
+        impl<$($gen)*> core::cmp::PartialEq<$Name> for $T {
+            #[inline]
+            fn eq(&self, other: &$Name) -> bool {
+                *self == other.0 as $T
+            }
+        }
+    };
+    (@partial_eq_bits [$opt:tt $($rest:tt)*] [$($gen:tt)*] $Name:ty, $T:ident) => {
+        $crate::bitfield! { @partial_eq_bits [$($rest)*] [$($gen)*] $Name, $T }
+    };
+
+    (@bitwise_ops [] [$($gen:tt)*] $Name:ty) => {};
+    (@bitwise_ops [bitwise_ops $($rest:tt)*] [$($gen:tt)*] $Name:ty) => {
+        $crate::bitfield! { @bitwise_op [$($gen)*] $Name, BitAnd::bitand, BitAndAssign::bitand_assign, & }
+        $crate::bitfield! { @bitwise_op [$($gen)*] $Name, BitOr::bitor, BitOrAssign::bitor_assign, | }
+        $crate::bitfield! { @bitwise_op [$($gen)*] $Name, BitXor::bitxor, BitXorAssign::bitxor_assign, ^ }
+    };
+    (@bitwise_ops [$opt:tt $($rest:tt)*] [$($gen:tt)*] $Name:ty) => {
+        $crate::bitfield! { @bitwise_ops [$($rest)*] [$($gen)*] $Name }
+    };
+    (@default [] [$($gen:tt)*] $Name:ty) => {};
+    (@default [default($($Field:ident = $val:expr),* $(,)?) $($rest:tt)*] [$($gen:tt)*] $Name:ty) => {
+        #[automatically_derived]
+        impl<$($gen)*> core::default::Default for $Name {
+            fn default() -> Self {
+                Self::new()
+                    $(.with(Self::$Field, $val))*
+            }
+        }
+    };
+    (@default [$opt:tt $($rest:tt)*] [$($gen:tt)*] $Name:ty) => {
+        $crate::bitfield! { @default [$($rest)*] [$($gen)*] $Name }
+    };
+
+    (@decode [] [$($gen:tt)*] $($rest:tt)*) => {};
+    (@decode [decode($Fields:ident) $($opts:tt)*] [$(const $G:ident: $GT:ty),*]
+        $vis:vis $Name:ident<$T:ident> {
+            $( [$($field_attr:tt)*] $field_vis:vis $Field:ident $(: $F:ty)? ),+
+        }
+    ) => {
+        #[doc = concat!(
+            "The unpacked value of every field of a [`", stringify!($Name), "`].\n\n",
+            "This is returned by [`", stringify!($Name), "::decode`].",
+        )]
+        #[derive(Debug)]
+        #[allow(non_snake_case)]
+        $vis struct $Fields {
+            $(
+                $($field_attr)*
+                $field_vis $Field: $crate::bitfield! { @decode_ty $T $(: $F)? },
+            )+
+        }
+
+        impl<$(const $G: $GT),*> $Name<$($G),*> {
+            #[doc = concat!(
+                "Unpacks every field of this bitfield into a [`", stringify!($Fields), "`].\n\n",
+                "# Panics\n\n",
+                "This method panics if any typed field does not contain a valid bit ",
+                "pattern for that field's type. See [`", stringify!($Name), "::get`].",
+            )]
+            $vis fn decode(&self) -> $Fields {
+                $Fields {
+                    $( $Field: self.get(Self::$Field), )+
+                }
+            }
+        }
+    };
+    (@decode [$opt:tt $($opts:tt)*] [$($gen:tt)*] $($rest:tt)*) => {
+        $crate::bitfield! { @decode [$($opts)*] [$($gen)*] $($rest)* }
+    };
+    (@masks [] [$($gen:tt)*] $($rest:tt)*) => {};
+    (@masks [masks($Masks:ident) $($opts:tt)*] [$(const $G:ident: $GT:ty),*]
+        $vis:vis $Name:ident, $Ty:ty {
+            $( [$($field_attr:tt)*] $field_vis:vis $Field:ident ),+
+        }
+    ) => {
+        #[doc = concat!(
+            "A [`", stringify!($Name), "`] with all bits of a single field set, ",
+            "for each field of a [`", stringify!($Name), "`].\n\n",
+            "These constants may be combined using the `bitwise_ops` option's ",
+            "`|` operator to build a set of flags.",
+        )]
+        #[derive(Debug)]
+        $vis struct $Masks<$(const $G: $GT),*>;
+
+        impl<$(const $G: $GT),*> $Masks<$($G),*> {
+            $(
+                $($field_attr)*
+                $field_vis const $Field: $Ty = <$Ty>::from_bits(<$Ty>::$Field.raw_mask() as _);
+            )+
+        }
+    };
+    (@masks [$opt:tt $($opts:tt)*] [$($gen:tt)*] $($rest:tt)*) => {
+        $crate::bitfield! { @masks [$($opts)*] [$($gen)*] $($rest)* }
+    };
+    (@unless_no_debug [] { $($body:tt)* }) => { $($body)* };
+    (@unless_no_debug [no_debug $($rest:tt)*] { $($body:tt)* }) => {};
+    (@unless_no_debug [$opt:tt $($rest:tt)*] { $($body:tt)* }) => {
+        $crate::bitfield! { @unless_no_debug [$($rest)*] { $($body)* } }
+    };
+    (@unless_no_display [] { $($body:tt)* }) => { $($body)* };
+    (@unless_no_display [no_display $($rest:tt)*] { $($body:tt)* }) => {};
+    (@unless_no_display [$opt:tt $($rest:tt)*] { $($body:tt)* }) => {
+        $crate::bitfield! { @unless_no_display [$($rest)*] { $($body)* } }
+    };
+    (@bits_accessor [] $vis:vis $Repr:ident) => {
+        $crate::bitfield! { @bits_fn $vis bits $Repr }
+    };
+    (@bits_accessor [bits_accessor($bits:ident) $($rest:tt)*] $vis:vis $Repr:ident) => {
+        $crate::bitfield! { @bits_fn $vis $bits $Repr }
+    };
+    (@bits_accessor [$opt:tt $($rest:tt)*] $vis:vis $Repr:ident) => {
+        $crate::bitfield! { @bits_accessor [$($rest)*] $vis $Repr }
+    };
+    (@bits_fn $vis:vis $bits:ident $Repr:ident) => {
+        /// Returns the raw bits of `self` as the bitfield's underlying
+        /// integer type.
+        ///
+        /// This is the inverse of [`from_bits`](Self::from_bits).
+        $vis const fn $bits(&self) -> $Repr {
+            self.0 as $Repr
+        }
+    };
+    (@exhaustive [] $Name:ident, $T:ident) => {};
+    (@exhaustive [exhaustive $($rest:tt)*] $Name:ident, $T:ident) => {
+        // Overlapping fields and fields that do not fit are already rejected,
+        // so the fields fill the bitfield iff their union covers every bit.
+        assert!(
+            Self::from_bits_truncate(!0).0 == <$T>::MAX,
+            concat!("fields of bitfield `", stringify!($Name), "` do not fill every bit of a `", stringify!($T), "`"),
+        );
+    };
+    (@exhaustive [$opt:tt $($rest:tt)*] $Name:ident, $T:ident) => {
+        $crate::bitfield! { @exhaustive [$($rest)*] $Name, $T }
+    };
+    (@decode_ty $T:ident: $F:ty) => { $F };
+    (@decode_ty $T:ident) => { $T };
+
+    (@bitwise_op [$($gen:tt)*] $Name:ty, $Op:ident::$op:ident, $OpAssign:ident::$op_assign:ident, $tok:tt) => {
+        #[automatically_derived]
+        impl<$($gen)*> core::ops::$Op for $Name {
+            type Output = Self;
+
+            #[inline]
+            fn $op(self, rhs: Self) -> Self {
+                Self(self.0 $tok rhs.0)
+            }
+        }
+
+        #[automatically_derived]
+        impl<$($gen)*> core::ops::$OpAssign for $Name {
+            #[inline]
+            fn $op_assign(&mut self, rhs: Self) {
+                self.0 = self.0 $tok rhs.0;
             }
         }
     };
+
     (@field<$T:ident>, prev: $Prev:ident:
         $(#[$meta:meta])*
         $vis:vis const $Field:ident = ..;
     ) => {
         $(#[$meta])*
-        $vis const $Field: $crate::bitfield!{ @t $T, $T, Self } = Self::$Prev.remaining();
+        $vis const $Field: $crate::bitfield!{ @t $T, $T, Self } = {
+            assert!(
+                Self::$Prev.most_significant_index() < <$T>::BITS,
+                concat!(
+                    "bitfield field `",
+                    stringify!($Field),
+                    "` is declared with `= ..`, but no bits remain in a `",
+                    stringify!($T),
+                    "`",
+                ),
+            );
+            Self::$Prev.remaining()
+        };
+    };
+    // Split fields must be matched before raw integer fields, whose width
+    // may be any single token (such as a `const` generic parameter).
+    (@field<$T:ident>, prev: $Prev:ident:
+        $(#[$meta:meta])*
+        $vis:vis const $Field:ident = [$width:literal $(@ $offset:literal)? $(, $($segments:tt)*)?];
+        $($rest:tt)*
+    ) => {
+        $(#[$meta])*
+        $vis const $Field: $crate::bitfield!{ @t $T, $T, Self } = {
+            let spec = $crate::bitfield! { @first_segment<$T> $Field, $Prev, $width $(@ $offset)? };
+            $crate::bitfield! { @segments<$T> $Field, spec, $($($segments)*)? }
+        };
+        $crate::bitfield!{ @field<$T>, prev: $Field: $($rest)* }
     };
+
     (@field<$T:ident>, prev: $Prev:ident:
         $(#[$meta:meta])*
-        $vis:vis const $Field:ident = $value:literal;
+        $vis:vis const $Field:ident = $value:tt;
         $($rest:tt)*
     ) => {
         $(#[$meta])*
-        $vis const $Field: $crate::bitfield!{ @t $T, $T, Self } = Self::$Prev.next($value);
+        $vis const $Field: $crate::bitfield!{ @t $T, $T, Self } = {
+            $crate::bitfield! { @assert_fits<$T> $Field, Self::$Prev.most_significant_index(), $value }
+            Self::$Prev.next($value)
+        };
         $crate::bitfield!{ @field<$T>, prev: $Field: $($rest)* }
     };
 
@@ -512,29 +2126,177 @@ macro_rules! bitfield {
         $($rest:tt)*
     ) => {
         $(#[$meta])*
-        $vis const $Field: $crate::bitfield!{ @t $T, $Val, Self } = Self::$Prev.then::<$Val>();
+        $vis const $Field: $crate::bitfield!{ @t $T, $Val, Self } = {
+            $crate::bitfield! { @assert_fits<$T> $Field, Self::$Prev.most_significant_index(), <$Val as $crate::FromBits<$T>>::BITS }
+            Self::$Prev.then::<$Val>()
+        };
+        $crate::bitfield!{ @field<$T>, prev: $Field: $($rest)* }
+    };
+
+
+    (@field<$T:ident>, prev: $Prev:ident:
+        $(#[$meta:meta])*
+        $vis:vis const $Field:ident @ $offset:literal = $value:tt;
+        $($rest:tt)*
+    ) => {
+        $(#[$meta])*
+        $vis const $Field: $crate::bitfield!{ @t $T, $T, Self } = {
+            $crate::bitfield! { @assert_after $Field, $Prev, $offset }
+            $crate::bitfield! { @assert_fits<$T> $Field, $offset, $value }
+            <$crate::bitfield!{ @t $T, $T, () }>::least_significant($offset).next($value).typed()
+        };
         $crate::bitfield!{ @field<$T>, prev: $Field: $($rest)* }
     };
 
+    (@field<$T:ident>, prev: $Prev:ident:
+        $(#[$meta:meta])*
+        $vis:vis const $Field:ident @ $offset:literal: $Val:ty;
+        $($rest:tt)*
+    ) => {
+        $(#[$meta])*
+        $vis const $Field: $crate::bitfield!{ @t $T, $Val, Self } = {
+            $crate::bitfield! { @assert_after $Field, $Prev, $offset }
+            $crate::bitfield! { @assert_fits<$T> $Field, $offset, <$Val as $crate::FromBits<$T>>::BITS }
+            <$crate::bitfield!{ @t $T, $T, () }>::least_significant($offset).then::<$Val>().typed()
+        };
+        $crate::bitfield!{ @field<$T>, prev: $Field: $($rest)* }
+    };
 
     (@field<$T:ident>, prev: $Prev:ident: ) => {  };
+
+    // The first range of a split field is placed like any other field.
+    (@first_segment<$T:ident> $Field:ident, $Prev:ident, $width:literal @ $offset:literal) => {{
+        $crate::bitfield! { @assert_after $Field, $Prev, $offset }
+        $crate::bitfield! { @assert_fits<$T> $Field, $offset, $width }
+        <$crate::bitfield!{ @t $T, $T, () }>::least_significant($offset).next($width).typed()
+    }};
+    (@first_segment<$T:ident> $Field:ident, $Prev:ident, $width:literal) => {{
+        $crate::bitfield! { @assert_fits<$T> $Field, Self::$Prev.most_significant_index(), $width }
+        Self::$Prev.next($width)
+    }};
+    (@first_segment<$T:ident> $Field:ident, $width:literal @ $offset:literal) => {{
+        $crate::bitfield! { @assert_fits<$T> $Field, $offset, $width }
+        <$crate::bitfield!{ @t $T, $T, () }>::least_significant($offset).next($width).typed()
+    }};
+    (@first_segment<$T:ident> $Field:ident, $width:literal) => {{
+        $crate::bitfield! { @assert_fits<$T> $Field, 0, $width }
+        <$crate::bitfield!{ @t $T, $T, () }>::least_significant($width).typed()
+    }};
+
+    // Each subsequent range of a split field is joined to the ranges before
+    // it, either immediately after them or at an explicit offset.
+    (@segments<$T:ident> $Field:ident, $spec:ident, ) => { $spec };
+    (@segments<$T:ident> $Field:ident, $spec:ident, $width:literal @ $offset:literal $(, $($rest:tt)*)?) => {{
+        assert!(
+            $offset >= $spec.most_significant_index(),
+            concat!(
+                "the bit ranges of split field `",
+                stringify!($Field),
+                "` must be declared in order from least- to most-significant",
+            ),
+        );
+        $crate::bitfield! { @assert_fits<$T> $Field, $offset, $width }
+        let $spec = $spec.join(&<$crate::bitfield!{ @t $T, $T, () }>::least_significant($offset).next($width).typed());
+        $crate::bitfield! { @segments<$T> $Field, $spec, $($($rest)*)? }
+    }};
+    (@segments<$T:ident> $Field:ident, $spec:ident, $width:literal $(, $($rest:tt)*)?) => {{
+        $crate::bitfield! { @assert_fits<$T> $Field, $spec.most_significant_index(), $width }
+        let $spec = $spec.join(&$spec.next($width));
+        $crate::bitfield! { @segments<$T> $Field, $spec, $($($rest)*)? }
+    }};
+
+    (@assert_after $Field:ident, $Prev:ident, $offset:literal) => {
+        // A field may be placed in the gap of a split field, so compare the
+        // offset with the end of the previous field's least-significant range
+        // of bits. For contiguous fields, this is the most-significant bit.
+        assert!(
+            $offset >= Self::$Prev.least_significant_index()
+                + (Self::$Prev.raw_mask() >> Self::$Prev.least_significant_index()).trailing_ones(),
+            concat!(
+                "bitfield field `",
+                stringify!($Field),
+                "` must be placed after the previous field `",
+                stringify!($Prev),
+                "`",
+            ),
+        );
+    };
+
+    (@assert_fits<$T:ident> $Field:ident, $start:expr, $bits:expr) => {
+        assert!(
+            $bits <= <$T>::BITS - $start,
+            concat!(
+                "bitfield field `",
+                stringify!($Field),
+                "` does not fit in the remaining bits of a `",
+                stringify!($T),
+                "`",
+            ),
+        );
+    };
+    (@field<$T:ident>:
+        $(#[$meta:meta])*
+        $vis:vis const $Field:ident = [$width:literal $(@ $offset:literal)? $(, $($segments:tt)*)?];
+        $($rest:tt)*
+    ) => {
+        $(#[$meta])*
+        $vis const $Field: $crate::bitfield!{ @t $T, $T, Self } = {
+            let spec = $crate::bitfield! { @first_segment<$T> $Field, $width $(@ $offset)? };
+            $crate::bitfield! { @segments<$T> $Field, spec, $($($segments)*)? }
+        };
+        $crate::bitfield!{ @field<$T>, prev: $Field: $($rest)* }
+    };
+
     (@field<$T:ident>:
         $(#[$meta:meta])*
-        $vis:vis const $Field:ident = $value:literal;
+        $vis:vis const $Field:ident = $value:tt;
         $($rest:tt)*
     ) => {
         $(#[$meta])*
-        $vis const $Field: $crate::bitfield!{ @t $T, $T, Self } = <$crate::bitfield!{ @t $T, $T, () }>::least_significant($value).typed();
+        $vis const $Field: $crate::bitfield!{ @t $T, $T, Self } = {
+            $crate::bitfield! { @assert_fits<$T> $Field, 0, $value }
+            <$crate::bitfield!{ @t $T, $T, () }>::least_significant($value).typed()
+        };
+        $crate::bitfield!{ @field<$T>, prev: $Field: $($rest)* }
+    };
+
+    (@field<$T:ident>:
+        $(#[$meta:meta])*
+        $vis:vis const $Field:ident @ $offset:literal = $value:tt;
+        $($rest:tt)*
+    ) => {
+        $(#[$meta])*
+        $vis const $Field: $crate::bitfield!{ @t $T, $T, Self } = {
+            $crate::bitfield! { @assert_fits<$T> $Field, $offset, $value }
+            <$crate::bitfield!{ @t $T, $T, () }>::least_significant($offset).next($value).typed()
+        };
+        $crate::bitfield!{ @field<$T>, prev: $Field: $($rest)* }
+    };
+
+    (@field<$T:ident>:
+        $(#[$meta:meta])*
+        $vis:vis const $Field:ident @ $offset:literal: $Val:ty;
+        $($rest:tt)*
+    ) => {
+        $(#[$meta])*
+        $vis const $Field: $crate::bitfield!{ @t $T, $Val, Self } = {
+            $crate::bitfield! { @assert_fits<$T> $Field, $offset, <$Val as $crate::FromBits<$T>>::BITS }
+            <$crate::bitfield!{ @t $T, $T, () }>::least_significant($offset).then::<$Val>().typed()
+        };
         $crate::bitfield!{ @field<$T>, prev: $Field: $($rest)* }
     };
 
+
     (@field<$T:ident>:
         $(#[$meta:meta])*
         $vis:vis const $Field:ident: $Val:ty;
         $($rest:tt)*
     ) => {
         $(#[$meta])*
-        $vis const $Field: $crate::bitfield!{ @t $T, $Val, Self } = <$crate::bitfield!{ @t $T, $Val, Self } >::first();
+        $vis const $Field: $crate::bitfield!{ @t $T, $Val, Self } = {
+            $crate::bitfield! { @assert_fits<$T> $Field, 0, <$Val as $crate::FromBits<$T>>::BITS }
+            <$crate::bitfield!{ @t $T, $Val, Self } >::first()
+        };
         $crate::bitfield!{ @field<$T>, prev: $Field: $($rest)* }
     };
 
@@ -612,19 +2374,114 @@ macro_rules! bitfield {
     // };
 
     (@t usize, $V:ty, $F:ty) => { $crate::PackUsize<$V, $F> };
+    (@t u128, $V:ty, $F:ty) => { $crate::Pack128<$V, $F> };
     (@t u64, $V:ty, $F:ty) => { $crate::Pack64<$V, $F> };
     (@t u32, $V:ty, $F:ty) => { $crate::Pack32<$V, $F> };
     (@t u16, $V:ty, $F:ty) => { $crate::Pack16<$V, $F> };
     (@t u8, $V:ty, $F:ty) => { $crate::Pack8<$V, $F> };
-    (@t $T:ty, $V:ty, $F:ty) => { compile_error!(concat!("unsupported bitfield type `", stringify!($T), "`; expected one of `usize`, `u64`, `u32`, `u16`, or `u8`")) }
+    (@t $T:ty, $V:ty, $F:ty) => { compile_error!(concat!("unsupported bitfield type `", stringify!($T), "`; expected one of `usize`, `u128`, `u64`, `u32`, `u16`, or `u8`")) }
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::FromBits;
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __bitfield_impl_serde {
+    ([$($gen:tt)*] $Name:ty, $T:ident) => {
+        #[automatically_derived]
+        impl<$($gen)*> $crate::__serde::Serialize for $Name {
+            fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::__serde::Serializer,
+            {
+                $crate::__serde::Serialize::serialize(&(self.0 as $T), serializer)
+            }
+        }
 
-    bitfield! {
-        #[allow(dead_code)]
+        #[automatically_derived]
+        impl<'de, $($gen)*> $crate::__serde::Deserialize<'de> for $Name {
+            fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+            where
+                D: $crate::__serde::Deserializer<'de>,
+            {
+                <$T as $crate::__serde::Deserialize<'de>>::deserialize(deserializer)
+                    .map(Self::from_bits)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __bitfield_impl_serde {
+    ([$($gen:tt)*] $Name:ty, $T:ident) => {};
+}
+
+#[cfg(feature = "arbitrary")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __bitfield_impl_arbitrary {
+    ([$($gen:tt)*] $Name:ty, $T:ident) => {
+        #[automatically_derived]
+        impl<'a, $($gen)*> $crate::__arbitrary::Arbitrary<'a> for $Name {
+            fn arbitrary(
+                u: &mut $crate::__arbitrary::Unstructured<'a>,
+            ) -> $crate::__arbitrary::Result<Self> {
+                let bits = <$T as $crate::__arbitrary::Arbitrary<'a>>::arbitrary(u)?;
+                let bits = Self::from_bits_truncate(bits).0 as $T;
+                <Self as core::convert::TryFrom<$T>>::try_from(bits)
+                    .map_err(|_| $crate::__arbitrary::Error::IncorrectFormat)
+            }
+
+            fn size_hint(depth: usize) -> (usize, Option<usize>) {
+                <$T as $crate::__arbitrary::Arbitrary<'a>>::size_hint(depth)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "arbitrary"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __bitfield_impl_arbitrary {
+    ([$($gen:tt)*] $Name:ty, $T:ident) => {};
+}
+
+#[cfg(feature = "tracing")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __bitfield_impl_tracing {
+    ([$($gen:tt)*] $vis:vis $Name:ty) => {
+        #[allow(dead_code)]
+        impl<$($gen)*> $Name {
+            /// Returns a `tracing::Value` which records this
+            /// bitfield using its [`fmt::Debug`](core::fmt::Debug)
+            /// implementation, with each field formatted separately.
+            ///
+            /// This allows a bitfield to be recorded as a `tracing` field
+            /// without a `?` or `%` sigil, as in
+            /// `tracing::info!(reg = bitfield.as_value())`.
+            #[inline]
+            $vis fn as_value(&self) -> $crate::__tracing_core::field::DebugValue<&Self> {
+                $crate::__tracing_core::field::debug(self)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __bitfield_impl_tracing {
+    ([$($gen:tt)*] $vis:vis $Name:ty) => {};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FromBits;
+
+    bitfield! {
+        #[allow(dead_code)]
         struct TestBitfield<u32> {
             const HELLO = 4;
             const _RESERVED_1 = 3;
@@ -664,6 +2521,333 @@ mod tests {
         }
     }
 
+    bitfield! {
+        struct TestDocs<u8> {
+            /// The low bits.
+            const LOW = 4;
+            /// A flag.
+            ///
+            /// With more docs.
+            #[allow(dead_code)]
+            const FLAG: bool;
+            const _RESERVED = ..;
+        }
+    }
+
+    bitfield! {
+        #[bitfield(default(LOW = 0b101))]
+        struct TestMustBe<u16> {
+            const LOW = 4;
+            const _MBO = 2 must_be(0b11);
+            const FLAG: bool;
+            const _MBZ = 1 must_be(0);
+            const HIGH = 4 must_be(0b1001);
+            const _RESERVED = ..;
+        }
+    }
+
+    bitfield! {
+        struct TestBitfield128<u128> {
+            const LOW = 48;
+            const _RESERVED = 16;
+            const FLAG: bool;
+            const BYTE: u8;
+            const HIGH = ..;
+        }
+    }
+
+    bitfield! {
+        #[bitfield(partial_eq_bits)]
+        #[derive(Eq, PartialEq)]
+        struct TestEqBits<u16> {
+            const LOW = 8;
+            const HIGH = 8;
+        }
+    }
+
+    bitfield! {
+        struct TestFlagArray<u16> {
+            const LOW = 3;
+            const FLAGS: [bool; 4];
+            const HIGH = ..;
+        }
+    }
+
+    bitfield! {
+        struct TestTuple<u32> {
+            const LOW = 3;
+            const PAIR: (bool, TestEnum);
+            const HIGH = ..;
+        }
+    }
+
+    bitfield! {
+        struct TestWide<u64> {
+            const ADDR = 40;
+            const FLAG: bool;
+            const _RESERVED = 7;
+            const KIND = 2;
+            const HIGH = ..;
+        }
+    }
+
+    bitfield! {
+        #[bitfield(decode(TestDecodeFields))]
+        struct TestDecode<u32> {
+            const LOW = 3;
+            const FLAG: bool;
+            const _RESERVED = 2;
+            const ENUM: TestEnum;
+            const HIGH = ..;
+        }
+    }
+
+    bitfield! {
+        #[bitfield(bitwise_ops, masks(TestMasksFlags))]
+        #[derive(PartialEq, Eq)]
+        struct TestMasks<u16> {
+            const READY: bool;
+            const ERROR: bool;
+            const _RESERVED = 2;
+            const CODE = 4;
+        }
+    }
+
+    bitfield! {
+        #[bitfield(bits_accessor(raw))]
+        struct TestAccessor<u16> {
+            const BITS = 4;
+            const VALUE = ..;
+        }
+    }
+
+    impl TestAccessor {
+        /// A method which would collide with the default `bits` accessor.
+        fn bits(&self) -> u16 {
+            self.get(Self::BITS)
+        }
+    }
+
+    bitfield! {
+        #[bitfield(no_debug, no_display)]
+        struct TestNoFmt<u16> {
+            const USER = 8;
+            const SECRET = 8;
+        }
+    }
+
+    impl core::fmt::Debug for TestNoFmt {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_struct("TestNoFmt")
+                .field("USER", &self.get(Self::USER))
+                .field("SECRET", &format_args!("<redacted>"))
+                .finish()
+        }
+    }
+
+    impl core::fmt::Display for TestNoFmt {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "user {}", self.get(Self::USER))
+        }
+    }
+
+    bitfield! {
+        #[bitfield(exhaustive)]
+        struct TestExhaustive<u16> {
+            const LOW = 5;
+            const _RESERVED = 3;
+            const FLAG: bool;
+            const HIGH = ..;
+        }
+    }
+
+    /// An enum with three variants, so that the all-ones pattern may be used
+    /// to represent `None`.
+    #[derive(Debug, Eq, PartialEq)]
+    enum TestMode {
+        Off = 0b00,
+        Low = 0b01,
+        High = 0b10,
+    }
+
+    impl FromBits<u32> for TestMode {
+        const BITS: u32 = 2;
+        type Error = crate::UnknownVariant;
+
+        fn try_from_bits(bits: u32) -> Result<Self, Self::Error> {
+            match bits {
+                0b00 => Ok(Self::Off),
+                0b01 => Ok(Self::Low),
+                0b10 => Ok(Self::High),
+                bits => Err(crate::UnknownVariant::new("TestMode", bits as u128)),
+            }
+        }
+
+        fn into_bits(self) -> u32 {
+            self as u32
+        }
+    }
+
+    bitfield! {
+        struct TestOption<u32> {
+            const MODE: Option<TestMode>;
+            const BYTE: Option<core::num::NonZeroU8>;
+            const REST = ..;
+        }
+    }
+
+    bitfield! {
+        struct TestNotes<u16> {
+            const PRESCALE = 4 => "log2 divisor";
+            const FLAG: bool;
+            const TIMEOUT = 8 => "ms";
+        }
+    }
+
+    bitfield! {
+        struct TestOffsets<u32> {
+            const FIRST @ 2 = 3;
+            const FLAG @ 8: bool;
+            const NEXT = 4;
+            const BYTE @ 20: u8;
+        }
+    }
+
+    bitfield! {
+        struct TestQualified<core::primitive::u16> {
+            const LOW = 4;
+            const FLAG: bool;
+            const HIGH = ..;
+        }
+    }
+
+    bitfield! {
+        struct TestQualifiedGeneric<::std::primitive::i32, const W: u32> {
+            const VALUE = W;
+            const REST = ..;
+        }
+    }
+
+    bitfield! {
+        #[derive(PartialEq, Eq)]
+        struct TestGeneric<u32, const W: u32> {
+            const FLAG: bool;
+            const VALUE = W;
+            const ENUM: TestEnum;
+            const HIGH @ 24 = 8;
+        }
+    }
+
+    bitfield! {
+        struct TestSigned<i32> {
+            const LOW = 12;
+            const SIGNED_BYTE: i8;
+            const REST = ..;
+        }
+    }
+
+    bitfield! {
+        #[bitfield(bitwise_ops)]
+        struct TestFlags<u8> {
+            const READ: bool;
+            const WRITE: bool;
+            const EXEC: bool;
+        }
+    }
+
+    bitfield! {
+        #[derive(Eq, PartialEq, Hash)]
+        struct TestHash<u8> {
+            const VECTOR = 5;
+            const PRIORITY = 3;
+        }
+    }
+
+    bitfield! {
+        #[derive(Eq, PartialEq)]
+        struct TestInnermost<u8> {
+            const FLAG: bool;
+            const VALUE = 7;
+        }
+    }
+
+    bitfield! {
+        #[derive(Eq, PartialEq)]
+        struct TestInner<u16> {
+            const KIND = 4;
+            const INNERMOST: TestInnermost;
+            const _RESERVED = ..;
+        }
+    }
+
+    bitfield! {
+        #[derive(Eq, PartialEq)]
+        struct TestOuter<u32> {
+            const LOW = 8;
+            const INNER: TestInner;
+            const HIGH = ..;
+        }
+    }
+
+    #[repr(u8)]
+    #[derive(Debug, Eq, PartialEq)]
+    enum TestEnum3 {
+        Foo = 0b00,
+        Bar = 0b01,
+        Baz = 0b10,
+    }
+
+    impl FromBits<u8> for TestEnum3 {
+        const BITS: u32 = 2;
+        type Error = &'static str;
+
+        fn try_from_bits(bits: u8) -> Result<Self, Self::Error> {
+            match bits {
+                0b00 => Ok(Self::Foo),
+                0b01 => Ok(Self::Bar),
+                0b10 => Ok(Self::Baz),
+                _ => Err("invalid bit pattern for `TestEnum3`"),
+            }
+        }
+
+        fn into_bits(self) -> u8 {
+            self as u8
+        }
+    }
+
+    bitfield! {
+        struct TestTryGet<u8> {
+            const LOW = 4;
+            const ENUM: TestEnum3;
+        }
+    }
+
+    bitfield! {
+        #[bitfield(default(ENABLED = true, MODE = 0b101, KIND = TestEnum3::Baz))]
+        struct TestDefault<u8> {
+            const ENABLED: bool;
+            const MODE = 3;
+            const _RESERVED = 2;
+            const KIND: TestEnum3;
+        }
+    }
+
+    bitfield! {
+        struct TestSplit<u16> {
+            const PRESENT: bool;
+            const ADDR = [4, 8 @ 8];
+            const FLAGS @ 5 = 3;
+        }
+    }
+
+    bitfield! {
+        struct TestSplitFirst<u32> {
+            const SPLIT = [2, 3 @ 8, 3];
+            const MID @ 2 = 6;
+            const REST @ 14 = 18;
+        }
+    }
+
     #[derive(Debug)]
     #[allow(dead_code)]
     struct TestDebug {
@@ -692,8 +2876,1476 @@ mod tests {
         println!("test_debug: {:?}", test_debug)
     }
 
+    #[test]
+    fn debug_invalid_field() {
+        // There is no `TestEnum3` variant for 0b11.
+        let bitfield = TestTryGet::from_bits(0b11_0101);
+        assert_eq!(
+            format!("{bitfield:?}"),
+            "TestTryGet { LOW: 5, ENUM: Err(0b11) }"
+        );
+        assert_eq!(
+            format!("{bitfield:#?}"),
+            "TestTryGet {\n    LOW: 5,\n    ENUM: Err(0b11),\n}"
+        );
+
+        let bitfield = TestTryGet::from_bits(0b10_0101);
+        assert_eq!(format!("{bitfield:?}"), "TestTryGet { LOW: 5, ENUM: Baz }");
+    }
+
+    #[test]
+    fn display_invalid_field() {
+        // There is no `TestEnum3` variant for 0b11.
+        let bitfield = TestTryGet::from_bits(0b11_0101);
+        let formatted = format!("{bitfield}");
+        let expected = [
+            "00110101",
+            "  └┤└┬─┘",
+            "   │ └── LOW: 5 (0101)",
+            "   └─── ENUM: Err(0b11) (11)",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(formatted, expected);
+
+        assert_eq!(
+            format!("{bitfield:#}"),
+            "TestTryGet { LOW: 5, ENUM: Err(0b11) }"
+        );
+
+        let formatted = format!("{bitfield:.8}");
+        let expected = [
+            "00110101",
+            "  └┤└┬─┘",
+            "   │ └─ LOW: 5 (0101)",
+            "   └── ENUM: Err(0b11) (11)",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(formatted, expected);
+    }
+
     #[test]
     fn macro_bitfield_valid() {
         TestBitfield::assert_valid();
     }
+
+    #[test]
+    fn macro_bitfield_128_valid() {
+        TestBitfield128::assert_valid();
+    }
+
+    #[test]
+    fn test_bitfield_128_format() {
+        let test_bitfield = TestBitfield128::new()
+            .with(TestBitfield128::LOW, 0xface)
+            .with(TestBitfield128::FLAG, true)
+            .with(TestBitfield128::BYTE, 0x5a)
+            .with(TestBitfield128::HIGH, 0xc0ffee);
+        assert_eq!(test_bitfield.get(TestBitfield128::LOW), 0xface);
+        assert!(test_bitfield.get(TestBitfield128::FLAG));
+        assert_eq!(test_bitfield.get(TestBitfield128::BYTE), 0x5a);
+        assert_eq!(test_bitfield.get(TestBitfield128::HIGH), 0xc0ffee);
+
+        let formatted = format!("{test_bitfield}");
+        println!("{formatted}");
+        let expected = r#"
+00000000000000000000000000000001100000011111111111011100101101010000000000000000000000000000000000000000000000001111101011001110
+└┬────────────────────────────────────────────────────┘└┬─────┘│                └┬─────────────────────────────────────────────┘
+ │                                                      │      │                 └────────────────────────────────────────────── LOW: 64206 (000000000000000000000000000000001111101011001110)
+ │                                                      │      └─────────────────────────────────────────────────────────────── FLAG: true (1)
+ │                                                      └────────────────────────────────────────────────────────────────────── BYTE: 90 (01011010)
+ └───────────────────────────────────────────────────────────────────────────────────────────────────────────────────────────── HIGH: 12648430 (0000000000000000000000000000000110000001111111111101110)
+"#.trim_start();
+        assert_eq!(formatted, expected);
+    }
+
+    #[test]
+    fn with_const() {
+        static STATIC_BITFIELD: TestBitfield = TestBitfield::new()
+            .with_const(TestBitfield::HELLO, 0b1001)
+            .with_const(TestBitfield::LOTS, 0b11010)
+            .with_const(TestBitfield::FUN, 9);
+        assert_eq!(STATIC_BITFIELD.0, (9 << 16) | (0b11010 << 10) | 0b1001);
+        assert_eq!(
+            STATIC_BITFIELD.0,
+            TestBitfield::new()
+                .with(TestBitfield::HELLO, 0b1001)
+                .with(TestBitfield::LOTS, 0b11010)
+                .with(TestBitfield::FUN, 9)
+                .0
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn with_const_out_of_range() {
+        TestBitfield::new().with_const(TestBitfield::HELLO, 0b1_0000);
+    }
+
+    #[test]
+    fn bool_flags() {
+        let mut test_bitfield = TestBitfield::new();
+        assert!(!test_bitfield.contains(TestBitfield::WORLD));
+        assert_eq!(
+            test_bitfield.contains(TestBitfield::WORLD),
+            test_bitfield.get(TestBitfield::WORLD)
+        );
+
+        test_bitfield.insert(TestBitfield::WORLD);
+        assert!(test_bitfield.contains(TestBitfield::WORLD));
+        assert_eq!(
+            test_bitfield.0,
+            TestBitfield::new().with(TestBitfield::WORLD, true).0
+        );
+
+        test_bitfield.remove(TestBitfield::WORLD);
+        assert!(!test_bitfield.contains(TestBitfield::WORLD));
+        assert_eq!(
+            test_bitfield.0,
+            TestBitfield::new().with(TestBitfield::WORLD, false).0
+        );
+    }
+
+    #[test]
+    fn bool_array() {
+        TestFlagArray::assert_valid();
+        let flags = [true, false, true, true];
+        let bitfield = TestFlagArray::new()
+            .with(TestFlagArray::LOW, 0b111)
+            .with(TestFlagArray::FLAGS, flags)
+            .with(TestFlagArray::HIGH, 0b1);
+        assert_eq!(bitfield.0, (0b1 << 7) | (0b1101 << 3) | 0b111);
+        assert_eq!(bitfield.get(TestFlagArray::FLAGS), flags);
+        assert_eq!(bitfield.get(TestFlagArray::LOW), 0b111);
+        assert_eq!(bitfield.get(TestFlagArray::HIGH), 0b1);
+    }
+
+    #[test]
+    fn tuple() {
+        use core::num::NonZeroU8;
+
+        TestTuple::assert_valid();
+        assert_eq!(<(bool, TestEnum) as FromBits<u32>>::BITS, 3);
+        assert_eq!(TestTuple::PAIR.least_significant_index(), 3);
+        assert_eq!(TestTuple::HIGH.least_significant_index(), 6);
+
+        let bitfield = TestTuple::new()
+            .with(TestTuple::LOW, 0b101)
+            .with(TestTuple::PAIR, (true, TestEnum::Baz))
+            .with(TestTuple::HIGH, 0b1);
+        assert_eq!(bitfield.0, (0b1 << 6) | (0b10 << 4) | (0b1 << 3) | 0b101);
+        assert!(matches!(
+            bitfield.get(TestTuple::PAIR),
+            (true, TestEnum::Baz)
+        ));
+        assert_eq!(bitfield.get(TestTuple::LOW), 0b101);
+        assert_eq!(bitfield.get(TestTuple::HIGH), 0b1);
+
+        // the first invalid element's error is returned.
+        assert_eq!(
+            <(NonZeroU8, NonZeroU8) as FromBits<u32>>::try_from_bits(0),
+            Err(crate::TupleError::First(
+                NonZeroU8::try_from(0).unwrap_err()
+            ))
+        );
+        assert!(matches!(
+            <(bool, NonZeroU8) as FromBits<u32>>::try_from_bits(0b1),
+            Err(crate::TupleError::Second(_))
+        ));
+        assert_eq!(
+            <(bool, NonZeroU8) as FromBits<u32>>::try_from_bits(0b101),
+            Ok((true, NonZeroU8::new(0b10).unwrap()))
+        );
+    }
+
+    #[test]
+    fn get_by_ref() {
+        /// A non-`Copy` type which holds a bitfield.
+        struct Holder {
+            bitfield: TestBitfield,
+        }
+
+        let holder = Holder {
+            bitfield: TestBitfield::new()
+                .with(TestBitfield::HELLO, 0b1010)
+                .with(TestBitfield::WORLD, true),
+        };
+        let bitfield: &TestBitfield = &holder.bitfield;
+        assert_eq!(TestBitfield::get(bitfield, TestBitfield::HELLO), 0b1010);
+        assert_eq!(
+            TestBitfield::try_get(bitfield, TestBitfield::WORLD),
+            Ok(true)
+        );
+        assert!(bitfield.get(TestBitfield::WORLD));
+        assert_eq!(holder.bitfield.get(TestBitfield::LOTS), 0);
+    }
+
+    #[test]
+    fn masks() {
+        assert_eq!(TestMasksFlags::READY.bits(), 0b1);
+        assert_eq!(TestMasksFlags::ERROR.bits(), 0b10);
+        assert_eq!(TestMasksFlags::_RESERVED.bits(), 0b1100);
+        assert_eq!(TestMasksFlags::CODE.bits(), 0b1111_0000);
+
+        let mut flags = TestMasksFlags::READY | TestMasksFlags::CODE;
+        assert!(flags.get(TestMasks::READY));
+        assert!(!flags.get(TestMasks::ERROR));
+        assert_eq!(flags.get(TestMasks::CODE), 0b1111);
+
+        flags |= TestMasksFlags::ERROR;
+        assert_eq!(
+            flags,
+            TestMasks::new()
+                .with(TestMasks::READY, true)
+                .with(TestMasks::ERROR, true)
+                .with(TestMasks::CODE, 0b1111)
+        );
+    }
+
+    #[test]
+    fn bits_accessor() {
+        use crate::BitfieldExt;
+
+        let bitfield = TestAccessor::new()
+            .with(TestAccessor::BITS, 0b1010)
+            .with(TestAccessor::VALUE, 0xabc);
+        assert_eq!(bitfield.raw(), 0xabca);
+        assert_eq!(bitfield.bits(), 0b1010);
+        assert_eq!(BitfieldExt::bits(&bitfield), 0xabca);
+        assert_eq!(u16::from(bitfield), 0xabca);
+        assert_eq!(TestAccessor::from_bits(bitfield.raw()).raw(), 0xabca);
+    }
+
+    #[test]
+    fn no_debug_no_display() {
+        let bitfield = TestNoFmt::new()
+            .with(TestNoFmt::USER, 42)
+            .with(TestNoFmt::SECRET, 0xff);
+        assert_eq!(
+            format!("{bitfield:?}"),
+            "TestNoFmt { USER: 42, SECRET: <redacted> }"
+        );
+        assert_eq!(format!("{bitfield}"), "user 42");
+        // other generated impls are unaffected.
+        assert_eq!(format!("{bitfield:b}"), "TestNoFmt(1111111100101010)");
+    }
+
+    #[test]
+    fn exhaustive() {
+        TestExhaustive::assert_valid();
+        const _: () = TestExhaustive::assert_valid_const();
+
+        // Every bit is declared, so truncation never clears anything.
+        let bitfield = TestExhaustive::from_bits_truncate(u16::MAX);
+        assert_eq!(bitfield.bits(), u16::MAX);
+    }
+
+    #[test]
+    fn decode() {
+        let bitfield = TestDecode::new()
+            .with(TestDecode::LOW, 0b101)
+            .with(TestDecode::FLAG, true)
+            .with(TestDecode::ENUM, TestEnum::Qux)
+            .with(TestDecode::HIGH, 0b10_0110);
+        let fields = bitfield.decode();
+        assert_eq!(fields.LOW, 0b101);
+        assert!(fields.FLAG);
+        assert_eq!(fields._RESERVED, 0);
+        assert!(matches!(fields.ENUM, TestEnum::Qux));
+        assert_eq!(fields.HIGH, 0b10_0110);
+
+        let TestDecodeFields { LOW, FLAG, .. } = TestDecode::new().decode();
+        assert_eq!(LOW, 0);
+        assert!(!FLAG);
+    }
+
+    #[test]
+    fn option() {
+        use core::num::NonZeroU8;
+
+        TestOption::assert_valid();
+        assert_eq!(TestOption::BYTE.least_significant_index(), 2);
+
+        // `Some` values are packed as the inner value.
+        let bitfield = TestOption::new()
+            .with(TestOption::MODE, Some(TestMode::High))
+            .with(TestOption::BYTE, NonZeroU8::new(0x42));
+        assert_eq!(bitfield.bits(), (0x42 << 2) | 0b10);
+        assert_eq!(bitfield.get(TestOption::MODE), Some(TestMode::High));
+        assert_eq!(bitfield.get(TestOption::BYTE), NonZeroU8::new(0x42));
+
+        // `None` is packed as all ones.
+        let bitfield = TestOption::new()
+            .with(TestOption::MODE, None)
+            .with(TestOption::BYTE, None);
+        assert_eq!(bitfield.bits(), (0xff << 2) | 0b11);
+        assert_eq!(bitfield.get(TestOption::MODE), None);
+        assert_eq!(bitfield.get(TestOption::BYTE), None);
+
+        // any other invalid pattern for the inner type is still an error.
+        let bitfield = TestOption::new().with(TestOption::MODE, Some(TestMode::Off));
+        assert_eq!(bitfield.get(TestOption::MODE), Some(TestMode::Off));
+        assert!(bitfield.try_get(TestOption::BYTE).is_err());
+    }
+
+    #[test]
+    fn explicit_offsets() {
+        TestOffsets::assert_valid();
+        assert_eq!(TestOffsets::FIRST.least_significant_index(), 2);
+        assert_eq!(TestOffsets::FLAG.least_significant_index(), 8);
+        assert_eq!(TestOffsets::NEXT.least_significant_index(), 9);
+        assert_eq!(TestOffsets::BYTE.least_significant_index(), 20);
+
+        let bitfield = TestOffsets::new()
+            .with(TestOffsets::FIRST, 0b101)
+            .with(TestOffsets::FLAG, true)
+            .with(TestOffsets::NEXT, 0b1001)
+            .with(TestOffsets::BYTE, 0xAB);
+        assert_eq!(
+            bitfield.0,
+            (0xAB << 20) | (0b1001 << 9) | (1 << 8) | (0b101 << 2)
+        );
+        assert_eq!(bitfield.get(TestOffsets::FIRST), 0b101);
+        assert!(bitfield.get(TestOffsets::FLAG));
+        assert_eq!(bitfield.get(TestOffsets::NEXT), 0b1001);
+        assert_eq!(bitfield.get(TestOffsets::BYTE), 0xAB);
+        println!("{bitfield}");
+    }
+
+    #[test]
+    fn layout_consts() {
+        // pin the layout of `TestOffsets` at compile time.
+        const _: () = {
+            assert!(TestOffsets::FIRST.offset() == 2);
+            assert!(TestOffsets::FIRST.width() == 3);
+            assert!(TestOffsets::FLAG.offset() == 8);
+            assert!(TestOffsets::FLAG.width() == 1);
+            assert!(TestOffsets::NEXT.offset() == 9);
+            assert!(TestOffsets::NEXT.width() == 4);
+            assert!(TestOffsets::BYTE.offset() == 20);
+            assert!(TestOffsets::BYTE.width() == 8);
+        };
+
+        const SPLIT_WIDTH: u32 = TestSplit::ADDR.width();
+        assert_eq!(SPLIT_WIDTH, 12);
+        assert_eq!(TestSplit::ADDR.offset(), 1);
+    }
+
+    #[test]
+    fn checked_with() {
+        let bitfield = TestBitfield::new()
+            .checked_with(TestBitfield::HELLO, 0b1001)
+            .expect("value fits in field")
+            .checked_with(TestBitfield::WORLD, true)
+            .expect("value fits in field");
+        assert_eq!(
+            bitfield.0,
+            TestBitfield::new()
+                .with(TestBitfield::HELLO, 0b1001)
+                .with(TestBitfield::WORLD, true)
+                .0
+        );
+
+        // `HELLO` is 4 bits wide, so a value with a 5th bit set won't fit.
+        let err = bitfield
+            .checked_with(TestBitfield::HELLO, 0b1_0000)
+            .expect_err("value does not fit in field");
+        assert_eq!(err, crate::OutOfRange::new(4, 5));
+        assert_eq!(err.width(), 4);
+        assert_eq!(err.value_bits(), 5);
+    }
+
+    #[test]
+    fn split_fields() {
+        TestSplit::assert_valid();
+        TestSplitFirst::assert_valid();
+        assert!(TestSplit::ADDR.is_split());
+        assert_eq!(TestSplit::ADDR.bits(), 12);
+        assert_eq!(TestSplitFirst::SPLIT.bits(), 8);
+        assert_eq!(TestSplitFirst::REST.least_significant_index(), 14);
+
+        for addr in 0..=0xFFF {
+            let split = TestSplit::new()
+                .with(TestSplit::PRESENT, true)
+                .with(TestSplit::ADDR, addr)
+                .with(TestSplit::FLAGS, 0b111);
+            assert_eq!(
+                split.bits(),
+                (addr >> 4) << 8 | 0b111 << 5 | (addr & 0xF) << 1 | 1,
+                "addr: {addr:#x}"
+            );
+            assert_eq!(split.get(TestSplit::ADDR), addr, "addr: {addr:#x}");
+            assert_eq!(split.get(TestSplit::FLAGS), 0b111, "addr: {addr:#x}");
+            assert!(split.get(TestSplit::PRESENT), "addr: {addr:#x}");
+        }
+
+        let mut split = TestSplitFirst::from_bits(u32::MAX);
+        split.set(TestSplitFirst::SPLIT, 0b1010_0110);
+        assert_eq!(
+            split.bits(),
+            0xFFFF_C000 | 0b101_001 << 8 | 0b11_1111 << 2 | 0b10
+        );
+        assert_eq!(split.get(TestSplitFirst::SPLIT), 0b1010_0110);
+        assert_eq!(split.get(TestSplitFirst::MID), 0b11_1111);
+        assert_eq!(
+            TestSplitFirst::new()
+                .checked_with(TestSplitFirst::SPLIT, 0x100)
+                .unwrap_err(),
+            crate::OutOfRange::new(8, 9)
+        );
+    }
+
+    #[test]
+    fn display_split() {
+        let split = TestSplit::new()
+            .with(TestSplit::PRESENT, true)
+            .with(TestSplit::ADDR, 0xABC)
+            .with(TestSplit::FLAGS, 0b010);
+        let formatted = format!("{split:-}");
+        let expected = [
+            "1010101101011001",
+            "└┬─────┘└┬┘└┬─┘│",
+            " │       │  │  └ PRESENT: true (1) [15]",
+            " └───────┼──┴───── ADDR: 2748 (101010111100) [0:7, 11:14]",
+            "         └─────── FLAGS: 2 (010) [8:10]",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(formatted, expected);
+    }
+
+    #[test]
+    fn display_unknown_bits() {
+        let test_bitfield = TestOffsets::new()
+            .with(TestOffsets::FIRST, 0b101)
+            .with(TestOffsets::FLAG, true)
+            .with(TestOffsets::BYTE, 0xff);
+        let formatted = format!("{test_bitfield:+}");
+        let mut lines = formatted.lines();
+        assert_eq!(lines.next(), Some("00001111111100000000000100010100"));
+        assert_eq!(lines.next(), Some("????└┬─────┘???????└┬─┘│???└┬┘??"));
+
+        // without the `+` flag, unknown bits are not marked.
+        let formatted = format!("{test_bitfield}");
+        assert!(!formatted.contains('?'), "{formatted}");
+    }
+
+    #[test]
+    fn display_msb0() {
+        let test_bitfield = TestBitfield::new()
+            .with(TestBitfield::HELLO, 0b1001)
+            .with(TestBitfield::WORLD, true)
+            .with(TestBitfield::LOTS, 0b11010);
+        let formatted = format!("{test_bitfield:-}");
+        let expected = [
+            "00000000000000000110100010001001",
+            "          └┬───┘│└┬──┘└┤│   └┬─┘",
+            "           │    │ │    ││    └─ HELLO: 9 (1001) [28:31]",
+            "           │    │ │    │└────── WORLD: true (1) [24]",
+            "           │    │ │    └──────── HAVE: Foo (00) [22:23]",
+            "           │    │ └───────────── LOTS: 26 (11010) [17:21]",
+            "           │    └───────────────── OF: 0 (0) [16]",
+            "           └───────────────────── FUN: 0 (000000) [10:15]",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(formatted, expected);
+    }
+
+    #[test]
+    fn display_grouped() {
+        let wide = TestWide::new()
+            .with(TestWide::ADDR, 0xAB_CDEF_0123)
+            .with(TestWide::FLAG, true)
+            .with(TestWide::KIND, 0b01)
+            .with(TestWide::HIGH, 0x2A5A);
+        let formatted = format!("{wide:.32}");
+        let expected = [
+            "10101001 01101001 00000001 10101011",
+            "└┬────────────┘└┤        │ └┬─────┘",
+            " │              │        │  └ ADDR: 737894400291 (1010101111001101111011110000000100100011)",
+            " │              │        └─── FLAG: true (1)",
+            " │              └──────────── KIND: 1 (01)",
+            " └─────────────────────────── HIGH: 10842 (10101001011010)",
+            "",
+            "11001101 11101111 00000001 00100011",
+            "└┬────────────────────────────────┘",
+            " └ ADDR: 737894400291 (1010101111001101111011110000000100100011)",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(formatted, expected);
+
+        // a precision of at least the width of the bitfield formats it in a
+        // single row.
+        let formatted = format!("{wide:.64}");
+        let mut lines = formatted.lines();
+        assert_eq!(
+            lines.next(),
+            Some("10101001 01101001 00000001 10101011 11001101 11101111 00000001 00100011")
+        );
+        assert_eq!(formatted.lines().count(), 6);
+    }
+
+    #[test]
+    fn display_annotations() {
+        let bitfield = TestNotes::new()
+            .with(TestNotes::PRESCALE, 3)
+            .with(TestNotes::TIMEOUT, 100);
+        let formatted = format!("{bitfield}");
+        let expected = [
+            "0000110010000011",
+            "   └┬─────┘│└┬─┘",
+            "    │      │ └─ PRESCALE: 3 (log2 divisor, 0011)",
+            "    │      └─────── FLAG: false (0)",
+            "    └─────────── TIMEOUT: 100 (ms, 01100100)",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(formatted, expected);
+
+        // annotations are also included in the grouped format.
+        let formatted = format!("{bitfield:.8}");
+        assert!(
+            formatted.contains("TIMEOUT: 100 (ms, 01100100)"),
+            "{formatted}"
+        );
+    }
+
+    #[test]
+    fn display_alternate() {
+        let test_bitfield = TestBitfield::new()
+            .with(TestBitfield::HELLO, 0b1001)
+            .with(TestBitfield::WORLD, true)
+            .with(TestBitfield::LOTS, 0b11010);
+        assert_eq!(
+            format!("{test_bitfield:#}"),
+            "TestBitfield { HELLO: 9, WORLD: true, HAVE: Foo, LOTS: 26, OF: 0, FUN: 0 }",
+        );
+    }
+
+    #[test]
+    fn display_truncated() {
+        let test_bitfield = TestBitfield::new()
+            .with(TestBitfield::HELLO, 0b1001)
+            .with(TestBitfield::WORLD, true)
+            .with(TestBitfield::LOTS, 0b11010);
+        let formatted = format!("{test_bitfield:13}");
+        let expected = [
+            "…100010001001",
+            "   └┤│   └┬─┘",
+            "    ││    └─ HELLO: 9 (1001)",
+            "    │└────── WORLD: true (1)",
+            "    └──────── HAVE: Foo (00)",
+            " (+3 more)",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(formatted, expected);
+    }
+
+    #[test]
+    fn qualified_repr() {
+        TestQualified::assert_valid();
+        let bitfield = TestQualified::from_bits(0xfeed);
+        let bits: u16 = bitfield.bits();
+        assert_eq!(bits, 0xfeed);
+        assert_eq!(bitfield.get(TestQualified::LOW), 0xd);
+        assert!(!bitfield.get(TestQualified::FLAG));
+        assert_eq!(bitfield.get(TestQualified::HIGH), 0xfee >> 1);
+        assert_eq!(TestQualified::HIGH.width(), 11);
+
+        type Generic = TestQualifiedGeneric<8>;
+        Generic::assert_valid();
+        let bitfield = Generic::from_bits(-1);
+        assert_eq!(bitfield.get(Generic::VALUE), 0xff);
+        assert_eq!(Generic::REST.width(), 24);
+    }
+
+    #[test]
+    fn const_generic_widths() {
+        type Narrow = TestGeneric<4>;
+        type Wide = TestGeneric<12>;
+        Narrow::assert_valid();
+        Wide::assert_valid();
+
+        const _: () = {
+            assert!(Narrow::VALUE.width() == 4);
+            assert!(Narrow::ENUM.offset() == 5);
+            assert!(Wide::VALUE.width() == 12);
+            assert!(Wide::ENUM.offset() == 13);
+        };
+
+        let narrow = Narrow::new()
+            .with(Narrow::FLAG, true)
+            .with(Narrow::VALUE, 0xF)
+            .with(Narrow::ENUM, TestEnum::Baz)
+            .with(Narrow::HIGH, 0xAB);
+        assert_eq!(narrow.bits(), (0xAB << 24) | (0b10 << 5) | (0xF << 1) | 1);
+        assert_eq!(narrow.get(Narrow::VALUE), 0xF);
+        assert!(matches!(narrow.get(Narrow::ENUM), TestEnum::Baz));
+        assert_eq!(narrow, Narrow::from_bits(narrow.bits()));
+        println!("{narrow}\n{narrow:?}");
+
+        let wide = Wide::new()
+            .with(Wide::FLAG, true)
+            .with(Wide::VALUE, 0xABC)
+            .with(Wide::ENUM, TestEnum::Baz)
+            .with(Wide::HIGH, 0xAB);
+        assert_eq!(wide.bits(), (0xAB << 24) | (0b10 << 13) | (0xABC << 1) | 1);
+        assert_eq!(wide.get(Wide::VALUE), 0xABC);
+        assert!(matches!(wide.get(Wide::ENUM), TestEnum::Baz));
+        assert_eq!(
+            format!("{wide:?}"),
+            "TestGeneric { FLAG: true, VALUE: 2748, ENUM: Baz, HIGH: 171 }"
+        );
+        println!("{wide}");
+    }
+
+    #[test]
+    fn signed() {
+        TestSigned::assert_valid();
+        let bitfield = TestSigned::from_bits(-1);
+        assert_eq!(bitfield.0, u32::MAX);
+        assert_eq!(bitfield.get(TestSigned::LOW), 0xFFF);
+        assert_eq!(bitfield.get(TestSigned::SIGNED_BYTE), -1);
+        assert_eq!(bitfield.get(TestSigned::REST), 0xFFF);
+
+        let bitfield = TestSigned::new()
+            .with(TestSigned::LOW, 0b1010)
+            .with(TestSigned::SIGNED_BYTE, -2);
+        assert_eq!(bitfield.0, (0xFE << 12) | 0b1010);
+        assert_eq!(bitfield.get(TestSigned::SIGNED_BYTE), -2);
+        assert_eq!(format!("{bitfield:b}"), "TestSigned(11111110000000001010)");
+        println!("{bitfield}");
+    }
+
+    #[test]
+    fn bitwise_ops() {
+        let a = TestFlags::new()
+            .with(TestFlags::READ, true)
+            .with(TestFlags::WRITE, true);
+        let b = TestFlags::new()
+            .with(TestFlags::WRITE, true)
+            .with(TestFlags::EXEC, true);
+
+        assert_eq!((a | b).0, TestFlags::from_bits(a.0 | b.0).0);
+        assert_eq!((a & b).0, TestFlags::from_bits(a.0 & b.0).0);
+        assert_eq!((a ^ b).0, TestFlags::from_bits(a.0 ^ b.0).0);
+
+        let mut c = a;
+        c |= b;
+        assert_eq!(c.0, (a | b).0);
+        c &= b;
+        assert_eq!(c.0, b.0);
+        c ^= a;
+        assert_eq!(c.0, (a ^ b).0);
+    }
+
+    #[test]
+    fn bits_roundtrip() {
+        for bits in [0, 1, 0b1010_0101, 0xDEAD_BEEF, u32::MAX] {
+            assert_eq!(TestBitfield::from_bits(bits).bits(), bits);
+        }
+        for bits in [0, 1, -1, i32::MIN, i32::MAX] {
+            assert_eq!(TestSigned::from_bits(bits).bits(), bits);
+        }
+        for bits in [0, 1, u128::MAX] {
+            assert_eq!(TestBitfield128::from_bits(bits).bits(), bits);
+        }
+    }
+
+    #[test]
+    fn bitfield_ext() {
+        use crate::BitfieldExt;
+
+        fn roundtrip<B>(bits: B::Repr) -> B::Repr
+        where
+            B: BitfieldExt + core::fmt::Debug,
+            B::Repr: core::fmt::Debug,
+        {
+            let bitfield = B::from_bits(bits);
+            println!("{bits:?} -> {bitfield:?}");
+            bitfield.bits()
+        }
+
+        assert_eq!(roundtrip::<TestBitfield>(0xDEAD_BEEF), 0xDEAD_BEEF);
+        assert_eq!(roundtrip::<TestSigned>(-1), -1);
+        assert_eq!(roundtrip::<TestBitfield128>(u128::MAX), u128::MAX);
+        assert_eq!(roundtrip::<TestFlags>(0b11), 0b11);
+    }
+
+    #[test]
+    fn remaining_field() {
+        assert_eq!(TestFlagArray::HIGH.bits(), u16::BITS - (3 + 4));
+        assert_eq!(TestFlagArray::HIGH.least_significant_index(), 3 + 4);
+        assert_eq!(TestSigned::REST.bits(), u32::BITS - (12 + 8));
+        assert_eq!(TestBitfield128::HIGH.bits(), u128::BITS - (48 + 16 + 1 + 8));
+
+        let bitfield = TestFlagArray::from_bits(u16::MAX);
+        assert_eq!(bitfield.get(TestFlagArray::HIGH), 0b1_1111_1111);
+
+        let bitfield = TestFlagArray::new()
+            .with(TestFlagArray::LOW, 0b101)
+            .with(TestFlagArray::HIGH, 0b1_0000_0011);
+        let expected = r#"
+1000000110000101
+└┬──────┘└┬─┘└┬┘
+ │        │   └── LOW: 5 (101)
+ │        └──── FLAGS: [false, false, false, false] (0000)
+ └────────────── HIGH: 259 (100000011)
+"#
+        .trim_start();
+        assert_eq!(format!("{bitfield}"), expected);
+    }
+
+    #[test]
+    fn derive_hash() {
+        use std::collections::HashMap;
+
+        let a = TestHash::new()
+            .with(TestHash::VECTOR, 3)
+            .with(TestHash::PRIORITY, 1);
+        let b = TestHash::new()
+            .with(TestHash::VECTOR, 3)
+            .with(TestHash::PRIORITY, 2);
+
+        let mut map = HashMap::new();
+        map.insert(a, "a");
+        map.insert(b, "b");
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&a), Some(&"a"));
+        assert_eq!(map.get(&TestHash::from_bits(b.bits())), Some(&"b"));
+    }
+
+    #[test]
+    fn from_bits_truncate() {
+        // All bits in `TestBitfield` are covered by a field (including the
+        // reserved field), except for the 10 most-significant bits.
+        let bitfield = TestBitfield::from_bits_truncate(u32::MAX);
+        assert_eq!(bitfield.bits(), u32::MAX >> 10);
+        assert_eq!(bitfield.get(TestBitfield::FUN), 0b11_1111);
+
+        // `TestOffsets` has unused bits between its fields.
+        let bitfield = TestOffsets::from_bits_truncate(u32::MAX);
+        assert_eq!(
+            bitfield.bits(),
+            (0xFF << 20) | (0b1111 << 9) | (1 << 8) | (0b111 << 2)
+        );
+
+        // Bitfields whose fields cover every bit are unchanged.
+        let bitfield = TestFlagArray::from_bits_truncate(u16::MAX);
+        assert_eq!(bitfield.bits(), u16::MAX);
+    }
+
+    #[test]
+    fn nested() {
+        TestInner::assert_valid();
+        TestOuter::assert_valid();
+        assert_eq!(TestOuter::INNER.bits(), 16);
+        assert_eq!(TestOuter::HIGH.bits(), 8);
+
+        let innermost = TestInnermost::new()
+            .with(TestInnermost::FLAG, true)
+            .with(TestInnermost::VALUE, 0x2a);
+        let inner = TestInner::new()
+            .with(TestInner::KIND, 0b1001)
+            .with(TestInner::INNERMOST, innermost);
+        let outer = TestOuter::new()
+            .with(TestOuter::LOW, 0xff)
+            .with(TestOuter::INNER, inner)
+            .with(TestOuter::HIGH, 0x12);
+        assert_eq!(outer.bits(), (0x12 << 24) | (0x0559 << 8) | 0xff);
+
+        assert_eq!(outer.try_get(TestOuter::INNER), Ok(inner));
+        let unpacked = outer.get(TestOuter::INNER);
+        assert_eq!(unpacked.try_get(TestInner::INNERMOST), Ok(innermost));
+        assert_eq!(
+            unpacked.get(TestInner::INNERMOST).get(TestInnermost::VALUE),
+            0x2a
+        );
+        assert!(unpacked.get(TestInner::INNERMOST).get(TestInnermost::FLAG));
+
+        println!("{outer}");
+        println!("{outer:#}");
+    }
+
+    #[test]
+    fn assert_valid_const() {
+        const _: () = TestBitfield::assert_valid_const();
+        const _: () = TestOffsets::assert_valid_const();
+        TestBitfield128::assert_valid_const();
+        TestSigned::assert_valid_const();
+    }
+
+    #[test]
+    fn get_opt() {
+        let bitfield = TestTryGet::new().with(TestTryGet::ENUM, TestEnum3::Bar);
+        assert_eq!(bitfield.get_opt(TestTryGet::ENUM), Some(TestEnum3::Bar));
+        assert_eq!(bitfield.get_opt(TestTryGet::LOW), Some(0));
+
+        // 0b11 is not a valid `TestEnum3`.
+        let invalid = TestTryGet::from_bits(0b11 << 4);
+        assert_eq!(invalid.get_opt(TestTryGet::ENUM), None);
+
+        let valid_enums = [bitfield, invalid, TestTryGet::new()]
+            .iter()
+            .filter_map(|b| b.get_opt(TestTryGet::ENUM))
+            .count();
+        assert_eq!(valid_enums, 2);
+    }
+
+    #[test]
+    fn increment_decrement() {
+        // `HELLO` is a 4-bit field at the bottom of `TestBitfield`.
+        let max = TestBitfield::HELLO.max_value();
+        let mut bitfield = TestBitfield::new()
+            .with(TestBitfield::HELLO, max - 1)
+            .with(TestBitfield::WORLD, true);
+
+        bitfield.wrapping_increment(TestBitfield::HELLO);
+        assert_eq!(bitfield.get(TestBitfield::HELLO), max);
+        // wrapping stays within the field.
+        bitfield.wrapping_increment(TestBitfield::HELLO);
+        assert_eq!(bitfield.get(TestBitfield::HELLO), 0);
+        assert!(bitfield.get(TestBitfield::WORLD));
+
+        bitfield.wrapping_decrement(TestBitfield::HELLO);
+        assert_eq!(bitfield.get(TestBitfield::HELLO), max);
+        assert!(bitfield.get(TestBitfield::WORLD));
+
+        let before = bitfield;
+        assert!(bitfield.checked_increment(TestBitfield::HELLO).is_none());
+        assert_eq!(bitfield.bits(), before.bits());
+
+        assert!(bitfield.checked_decrement(TestBitfield::HELLO).is_some());
+        assert_eq!(bitfield.get(TestBitfield::HELLO), max - 1);
+        assert!(bitfield.checked_increment(TestBitfield::HELLO).is_some());
+        assert_eq!(bitfield.get(TestBitfield::HELLO), max);
+
+        let mut bitfield = TestBitfield::new().with(TestBitfield::WORLD, true);
+        let before = bitfield;
+        assert!(bitfield.checked_decrement(TestBitfield::HELLO).is_none());
+        assert_eq!(bitfield.bits(), before.bits());
+        bitfield
+            .checked_increment(TestBitfield::HELLO)
+            .unwrap()
+            .checked_increment(TestBitfield::HELLO)
+            .unwrap();
+        assert_eq!(bitfield.get(TestBitfield::HELLO), 2);
+        assert!(bitfield.get(TestBitfield::WORLD));
+    }
+
+    #[test]
+    fn toggle() {
+        let original = TestBitfield::new()
+            .with(TestBitfield::HELLO, 0b1001)
+            .with(TestBitfield::LOTS, 0b11010);
+        let mut test_bitfield = original;
+
+        test_bitfield.toggle(TestBitfield::WORLD);
+        assert!(test_bitfield.get(TestBitfield::WORLD));
+        assert_eq!(test_bitfield.get(TestBitfield::HELLO), 0b1001);
+        assert_eq!(test_bitfield.get(TestBitfield::LOTS), 0b11010);
+
+        test_bitfield.toggle(TestBitfield::WORLD);
+        assert!(!test_bitfield.get(TestBitfield::WORLD));
+        assert_eq!(test_bitfield.0, original.0);
+    }
+
+    #[test]
+    fn partial_eq_bits() {
+        let bitfield = TestEqBits::new()
+            .with(TestEqBits::LOW, 0xed)
+            .with(TestEqBits::HIGH, 0xfe);
+        assert!(bitfield == 0xfeed);
+        assert!(0xfeed == bitfield);
+        assert!(bitfield != 0xface);
+        assert!(0xface != bitfield);
+        assert_eq!(bitfield, TestEqBits::from_bits(0xfeed));
+    }
+
+    #[test]
+    fn fields_iter() {
+        let test_bitfield = TestBitfield::new()
+            .with(TestBitfield::HELLO, 0b1001)
+            .with(TestBitfield::WORLD, true)
+            .with(TestBitfield::HAVE, TestEnum::Baz)
+            .with(TestBitfield::LOTS, 0b11010)
+            .with(TestBitfield::OF, 0)
+            .with(TestBitfield::FUN, 9);
+        let fields = test_bitfield.fields().collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                ("HELLO", 0b1001),
+                ("WORLD", 1),
+                ("HAVE", 0b10),
+                ("LOTS", 0b11010),
+                ("OF", 0),
+                ("FUN", 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff() {
+        let old = TestExhaustive::new()
+            .with(TestExhaustive::LOW, 0b10101)
+            .with(TestExhaustive::FLAG, false)
+            .with(TestExhaustive::HIGH, 0x42);
+        let new = old
+            .with(TestExhaustive::LOW, 0b01010)
+            .with(TestExhaustive::HIGH, 0x7f);
+        let diff = old.diff(&new).collect::<Vec<_>>();
+        assert_eq!(diff, vec![("LOW", 0b10101, 0b01010), ("HIGH", 0x42, 0x7f)]);
+
+        // reserved fields are not diffed.
+        let reserved = TestExhaustive::from_bits(old.bits() | (0b111 << 5));
+        assert_eq!(old.diff(&reserved).count(), 0);
+        assert_eq!(old.diff(&old).count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "arbitrary")]
+    fn arbitrary() {
+        use crate::__arbitrary::{Arbitrary, Error, Unstructured};
+
+        // All bits not covered by a field are cleared.
+        let mut u = Unstructured::new(&[0xff; 4]);
+        let bitfield = TestBitfield::arbitrary(&mut u).unwrap();
+        assert_eq!(bitfield.bits(), u32::MAX >> 10);
+
+        let mut u = Unstructured::new(&[0xff; 4]);
+        let bitfield = TestOffsets::arbitrary(&mut u).unwrap();
+        assert_eq!(
+            bitfield.bits(),
+            TestOffsets::from_bits_truncate(u32::MAX).bits()
+        );
+
+        // `TestTryGet::ENUM` has no variant for `0b11`, so the input is
+        // rejected rather than producing an invalid bitfield.
+        let mut u = Unstructured::new(&[0xff; 4]);
+        assert!(matches!(
+            TestTryGet::arbitrary(&mut u),
+            Err(Error::IncorrectFormat)
+        ));
+
+        // Every bitfield produced from arbitrary input has valid fields. One
+        // in four bit patterns of `ENUM` is invalid.
+        let data = (0..=255u8).collect::<Vec<_>>();
+        let mut u = Unstructured::new(&data);
+        let mut valid = 0;
+        for _ in 0..data.len() {
+            if let Ok(bitfield) = TestTryGet::arbitrary(&mut u) {
+                assert!(bitfield.try_get(TestTryGet::ENUM).is_ok());
+                valid += 1;
+            }
+        }
+        assert_eq!(valid, 192);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrip() {
+        macro_rules! test_serde_roundtrip {
+            ($($Name:ident<$T:ident>($bits:expr);)+) => {
+                $(
+                    bitfield! {
+                        #[derive(PartialEq, Eq)]
+                        struct $Name<$T> {
+                            const LOW = 4;
+                            const FLAG: bool;
+                            const HIGH = ..;
+                        }
+                    }
+
+                    let bitfield = $Name::from_bits($bits);
+                    let json = serde_json::to_string(&bitfield).unwrap();
+                    assert_eq!(json, serde_json::to_string(&($bits as $T)).unwrap());
+                    let deserialized: $Name = serde_json::from_str(&json).unwrap();
+                    assert_eq!(bitfield, deserialized);
+                )+
+            };
+        }
+
+        test_serde_roundtrip! {
+            Serde8<u8>(0b1011_0110);
+            Serde16<u16>(0xface);
+            Serde32<u32>(0xface_feed);
+            Serde64<u64>(0xface_feed_c0ff_ee00);
+            Serde128<u128>(0xface_feed_c0ff_ee00_dead_beef_f00d_cafe);
+            SerdeUsize<usize>(0xface);
+        }
+    }
+
+    #[test]
+    fn default_option() {
+        let bitfield = TestDefault::default();
+        assert_eq!(bitfield.bits(), 0b1000_1011);
+        assert!(bitfield.get(TestDefault::ENABLED));
+        assert_eq!(bitfield.get(TestDefault::MODE), 0b101);
+        assert_eq!(bitfield.get(TestDefault::KIND), TestEnum3::Baz);
+    }
+
+    #[test]
+    fn get_set_range() {
+        let mut bitfield = TestBitfield::from_bits(0xdead_beef);
+        assert_eq!(bitfield.get_range(0, 4), 0xf);
+        assert_eq!(bitfield.get_range(4, 8), 0xee);
+        assert_eq!(bitfield.get_range(16, 16), 0xdead);
+        assert_eq!(bitfield.get_range(0, 32), 0xdead_beef);
+        assert_eq!(bitfield.get_range(32, 0), 0);
+        assert_eq!(bitfield.get_range(0, 4), bitfield.get(TestBitfield::HELLO));
+
+        bitfield.set_range(8, 8, 0x42);
+        assert_eq!(bitfield.bits(), 0xdead_42ef);
+        bitfield.set_range(0, 32, 0);
+        assert_eq!(bitfield.bits(), 0);
+        bitfield.set_range(31, 1, 1);
+        assert_eq!(bitfield.bits(), 1 << 31);
+    }
+
+    #[test]
+    #[should_panic(expected = "bit range 30..33 is out of range for a `u32`")]
+    fn get_range_out_of_range() {
+        TestBitfield::new().get_range(30, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "value 0x10 does not fit in 4 bits")]
+    fn set_range_value_too_wide() {
+        TestBitfield::new().set_range(4, 4, 0x10);
+    }
+
+    #[test]
+    fn bit_set_bit() {
+        let mut bitfield = TestBitfield::from_bits(0b1010);
+        assert!(!bitfield.bit(0));
+        assert!(bitfield.bit(1));
+        assert!(bitfield.bit(3));
+        assert!(!bitfield.bit(31));
+
+        bitfield.set_bit(0, true).set_bit(1, false);
+        assert_eq!(bitfield.bits(), 0b1001);
+
+        // the most-significant bit.
+        bitfield.set_bit(31, true);
+        assert!(bitfield.bit(31));
+        assert_eq!(bitfield.bits(), (1 << 31) | 0b1001);
+        bitfield.set_bit(31, false);
+        assert_eq!(bitfield.bits(), 0b1001);
+
+        let mut signed = TestSigned::from_bits(0);
+        signed.set_bit(31, true);
+        assert_eq!(signed.bits(), i32::MIN);
+        assert!(signed.bit(31));
+    }
+
+    #[test]
+    #[should_panic(expected = "bit range 32..33 is out of range for a `u32`")]
+    fn bit_out_of_range() {
+        TestBitfield::new().bit(32);
+    }
+
+    #[test]
+    #[should_panic(expected = "bit range 8..9 is out of range for a `u8`")]
+    fn set_bit_out_of_range() {
+        TestFlags::new().set_bit(8, true);
+    }
+
+    #[test]
+    fn set_bit_indices() {
+        let bitfield = TestBitfield::from_bits(0b1000_0000_0000_0000_0000_0010_1001);
+        let indices = bitfield.set_bit_indices().collect::<Vec<_>>();
+        assert_eq!(indices, vec![0, 3, 5, 27]);
+
+        assert_eq!(TestBitfield::new().set_bit_indices().next(), None);
+        assert_eq!(
+            TestBitfield::from_bits(u32::MAX).set_bit_indices().count(),
+            32
+        );
+        assert_eq!(TestSigned::from_bits(-1).set_bit_indices().last(), Some(31));
+    }
+
+    #[test]
+    fn try_from_bits() {
+        use core::convert::TryFrom;
+
+        let bitfield = TestTryGet::try_from(0b10_1010).unwrap();
+        assert_eq!(bitfield.get(TestTryGet::ENUM), TestEnum3::Baz);
+        assert_eq!(bitfield.get(TestTryGet::LOW), 0b1010);
+
+        // 0b11 is not a valid `TestEnum3`.
+        let err = TestTryGet::try_from(0b11_1010).unwrap_err();
+        assert_eq!(err.field(), "ENUM");
+        assert_eq!(err.to_string(), "invalid bit pattern for field `ENUM`");
+
+        // bitfields without fallible typed fields always convert.
+        assert!(TestBitfield::try_from(u32::MAX).is_ok());
+    }
+
+    #[test]
+    fn into_bits() {
+        let bitfield = TestBitfield::new()
+            .with(TestBitfield::HELLO, 0b1001)
+            .with(TestBitfield::WORLD, true);
+        assert_eq!(u32::from(bitfield), bitfield.bits());
+
+        let bits: u32 = bitfield.into();
+        let round_tripped = TestBitfield::from_bits(bits);
+        assert_eq!(round_tripped.get(TestBitfield::HELLO), 0b1001);
+        assert!(round_tripped.get(TestBitfield::WORLD));
+
+        let bits: i32 = TestSigned::from_bits(-1).into();
+        assert_eq!(bits, -1);
+    }
+
+    #[test]
+    #[cfg(feature = "tracing")]
+    fn as_value() {
+        use std::sync::{Arc, Mutex};
+        use tracing::{
+            field::{Field, Visit},
+            span, Event, Metadata, Subscriber,
+        };
+
+        /// A subscriber which records the `Debug` output of every event field.
+        struct Recorder(Arc<Mutex<Vec<(&'static str, String)>>>);
+
+        impl Visit for Recorder {
+            fn record_debug(&mut self, field: &Field, value: &dyn core::fmt::Debug) {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push((field.name(), format!("{value:?}")));
+            }
+        }
+
+        impl Subscriber for Recorder {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+                span::Id::from_u64(1)
+            }
+            fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+            fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+            fn event(&self, event: &Event<'_>) {
+                event.record(&mut Recorder(self.0.clone()));
+            }
+            fn enter(&self, _: &span::Id) {}
+            fn exit(&self, _: &span::Id) {}
+        }
+
+        let bitfield = TestBitfield::new()
+            .with(TestBitfield::HELLO, 0b1001)
+            .with(TestBitfield::WORLD, true);
+        let recorded = Arc::new(Mutex::new(Vec::new()));
+        tracing::subscriber::with_default(Recorder(recorded.clone()), || {
+            tracing::info!(reg = bitfield.as_value());
+        });
+
+        let recorded = recorded.lock().unwrap();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0], ("reg", format!("{bitfield:?}")));
+        assert!(recorded[0].1.contains("HELLO: 9"), "{}", recorded[0].1);
+    }
+
+    #[test]
+    fn replace() {
+        let mut bitfield = TestBitfield::new()
+            .with(TestBitfield::HELLO, 0b1001)
+            .with(TestBitfield::HAVE, TestEnum::Bar);
+
+        assert_eq!(bitfield.replace(TestBitfield::HELLO, 0b0110), 0b1001);
+        assert_eq!(bitfield.get(TestBitfield::HELLO), 0b0110);
+
+        assert!(matches!(
+            bitfield.replace(TestBitfield::HAVE, TestEnum::Baz),
+            TestEnum::Bar
+        ));
+        assert!(matches!(bitfield.get(TestBitfield::HAVE), TestEnum::Baz));
+
+        assert!(!bitfield.replace(TestBitfield::WORLD, true));
+        assert!(bitfield.replace(TestBitfield::WORLD, false));
+
+        // other fields are unchanged.
+        assert_eq!(bitfield.get(TestBitfield::HELLO), 0b0110);
+    }
+
+    #[test]
+    fn build() {
+        let built = TestBitfield::build(|b| {
+            b.set(TestBitfield::HELLO, 0b1001)
+                .set(TestBitfield::WORLD, true)
+                .set(TestBitfield::HAVE, TestEnum::Baz);
+            b.set(TestBitfield::LOTS, 0b11010);
+        });
+        let chained = TestBitfield::new()
+            .with(TestBitfield::HELLO, 0b1001)
+            .with(TestBitfield::WORLD, true)
+            .with(TestBitfield::HAVE, TestEnum::Baz)
+            .with(TestBitfield::LOTS, 0b11010);
+        assert_eq!(built.bits(), chained.bits());
+    }
+
+    #[test]
+    fn field_docs() {
+        assert_eq!(
+            TestDocs::field_docs(),
+            &[
+                ("LOW", " The low bits."),
+                ("FLAG", " A flag.\n\n With more docs."),
+                ("_RESERVED", ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn with_saturating() {
+        // in range
+        let bitfield = TestBitfield::new().with_saturating(TestBitfield::LOTS, 0b1_0101);
+        assert_eq!(bitfield.get(TestBitfield::LOTS), 0b1_0101);
+
+        // overflow saturates to the field's maximum, without clobbering
+        // adjacent fields.
+        let bitfield = TestBitfield::new().with_saturating(TestBitfield::LOTS, 0xff);
+        assert_eq!(bitfield.get(TestBitfield::LOTS), 0b1_1111);
+        assert_eq!(bitfield.get(TestBitfield::OF), 0);
+        assert_eq!(bitfield.get(TestBitfield::HAVE).into_bits(), 0);
+
+        // the minimum value is packed as-is.
+        let bitfield = TestBitfield::from_bits(u32::MAX).with_saturating(TestBitfield::LOTS, 0);
+        assert_eq!(bitfield.get(TestBitfield::LOTS), 0);
+
+        // signed typed fields are never clamped.
+        let bitfield = TestSigned::new().with_saturating(TestSigned::SIGNED_BYTE, -128);
+        assert_eq!(bitfield.get(TestSigned::SIGNED_BYTE), -128);
+        assert_eq!(bitfield.get(TestSigned::REST), 0);
+        let bitfield = TestSigned::new().with_saturating(TestSigned::SIGNED_BYTE, 127);
+        assert_eq!(bitfield.get(TestSigned::SIGNED_BYTE), 127);
+    }
+
+    #[test]
+    fn must_be() {
+        const NEW: TestMustBe = TestMustBe::new();
+        assert_eq!(NEW.bits(), 0b1001_0011_0000);
+        assert_eq!(TestMustBe::default().bits(), 0b1001_0011_0101);
+        assert_eq!(
+            TestMustBe::build(|b| {
+                b.insert(TestMustBe::FLAG);
+            })
+            .bits(),
+            0b1001_0111_0000
+        );
+
+        // `from_bits` does not apply required values.
+        assert_eq!(TestMustBe::from_bits(0).bits(), 0);
+
+        // reserved fields are skipped when formatting.
+        assert_eq!(
+            format!("{NEW:#}"),
+            "TestMustBe { LOW: 0, FLAG: false, HIGH: 9 }"
+        );
+        assert_eq!(
+            format!("{NEW:?}"),
+            "TestMustBe { LOW: 0, FLAG: false, HIGH: 9 }"
+        );
+    }
+
+    #[test]
+    fn record_fields() {
+        let test_bitfield = TestBitfield::new()
+            .with(TestBitfield::HELLO, 0b1001)
+            .with(TestBitfield::WORLD, true)
+            .with(TestBitfield::HAVE, TestEnum::Baz)
+            .with(TestBitfield::LOTS, 0b11010);
+        let mut recorded = Vec::new();
+        test_bitfield.record_fields(|name, value| recorded.push((name, format!("{value:?}"))));
+        assert_eq!(
+            recorded,
+            vec![
+                ("HELLO", "9".to_string()),
+                ("WORLD", "true".to_string()),
+                ("HAVE", "Baz".to_string()),
+                ("LOTS", "26".to_string()),
+                ("OF", "0".to_string()),
+                ("FUN", "0".to_string()),
+            ]
+        );
+
+        // There is no `TestEnum3` variant for 0b11.
+        let mut recorded = Vec::new();
+        TestTryGet::from_bits(0b11_0101)
+            .record_fields(|name, value| recorded.push((name, format!("{value:?}"))));
+        assert_eq!(
+            recorded,
+            vec![("LOW", "5".to_string()), ("ENUM", "Err(0b11)".to_string())]
+        );
+    }
+
+    #[test]
+    fn is_empty() {
+        assert!(TestFlags::new().is_empty());
+        assert!(!TestFlags::new().any());
+
+        let mut flags = TestFlags::new();
+        flags.insert(TestFlags::EXEC);
+        assert!(!flags.is_empty());
+        assert!(flags.any());
+        flags.remove(TestFlags::EXEC);
+        assert!(flags.is_empty());
+
+        // required values are part of the empty baseline.
+        assert!(TestMustBe::new().is_empty());
+        assert!(TestMustBe::new().with(TestMustBe::FLAG, true).any());
+        // a bitfield with the required values cleared is not empty.
+        assert!(TestMustBe::from_bits(0).any());
+    }
+
+    #[test]
+    fn count_ones_zeros() {
+        assert_eq!(TestBitfield::new().count_ones(), 0);
+        assert_eq!(TestBitfield::new().count_zeros(), 32);
+
+        let bitfield = TestBitfield::from_bits(0xdead_beef);
+        assert_eq!(bitfield.count_ones(), 24);
+        assert_eq!(bitfield.count_zeros(), 8);
+
+        let flags = TestFlags::new()
+            .with(TestFlags::READ, true)
+            .with(TestFlags::EXEC, true);
+        assert_eq!(flags.count_ones(), 2);
+        assert_eq!(flags.count_zeros(), 6);
+
+        // signed bitfields count the bits of their two's complement
+        // representation.
+        assert_eq!(TestSigned::from_bits(-1).count_ones(), 32);
+        assert_eq!(TestSigned::from_bits(i32::MIN).count_zeros(), 31);
+    }
+
+    #[cfg(feature = "derive")]
+    #[repr(u8)]
+    #[derive(Debug, Eq, PartialEq, crate::FromBits)]
+    enum Derived3 {
+        Foo,
+        Bar,
+        Baz,
+    }
+
+    #[cfg(feature = "derive")]
+    #[repr(u16)]
+    #[derive(Debug, Eq, PartialEq, crate::FromBits)]
+    enum Derived4 {
+        Foo = 0b00,
+        Bar = 0b01,
+        Baz = 0b10,
+        Qux = 0b11,
+    }
+
+    #[cfg(feature = "derive")]
+    #[repr(u8)]
+    #[derive(Debug, Eq, PartialEq, crate::FromBits)]
+    enum DerivedSparse {
+        Foo = 0,
+        Bar = 3,
+        Baz = 7,
+    }
+
+    #[cfg(feature = "derive")]
+    bitfield! {
+        struct TestDerived<u32> {
+            const THREE: Derived3;
+            const FOUR: Derived4;
+            const REST = ..;
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "derive")]
+    fn derive_from_bits_3_variants() {
+        assert_eq!(<Derived3 as FromBits<u8>>::BITS, 2);
+        assert_eq!(<Derived3 as FromBits<u64>>::BITS, 2);
+
+        for (bits, variant) in [
+            (0b00, Derived3::Foo),
+            (0b01, Derived3::Bar),
+            (0b10, Derived3::Baz),
+        ] {
+            assert_eq!(
+                <Derived3 as FromBits<u32>>::try_from_bits(bits),
+                Ok(variant)
+            );
+        }
+        assert_eq!(FromBits::<u32>::into_bits(Derived3::Baz), 0b10);
+
+        let err = <Derived3 as FromBits<u32>>::try_from_bits(0b11).unwrap_err();
+        assert_eq!(err, crate::UnknownVariant::new("Derived3", 0b11));
+        assert_eq!(
+            err.to_string(),
+            "invalid bit pattern 0b11 for `Derived3`: no variant has this discriminant"
+        );
+
+        let bitfield = TestDerived::new().with(TestDerived::THREE, Derived3::Bar);
+        assert_eq!(bitfield.get(TestDerived::THREE), Derived3::Bar);
+        assert!(TestDerived::from_bits(0b11)
+            .try_get(TestDerived::THREE)
+            .is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "derive")]
+    fn derive_from_bits_4_variants() {
+        assert_eq!(<Derived4 as FromBits<u16>>::BITS, 2);
+        assert_eq!(<Derived4 as FromBits<u128>>::BITS, 2);
+
+        for (bits, variant) in [
+            (0b00, Derived4::Foo),
+            (0b01, Derived4::Bar),
+            (0b10, Derived4::Baz),
+            (0b11, Derived4::Qux),
+        ] {
+            assert_eq!(
+                <Derived4 as FromBits<u32>>::try_from_bits(bits),
+                Ok(variant)
+            );
+        }
+        assert_eq!(FromBits::<u32>::into_bits(Derived4::Qux), 0b11);
+
+        let bitfield = TestDerived::new()
+            .with(TestDerived::THREE, Derived3::Baz)
+            .with(TestDerived::FOUR, Derived4::Bar);
+        assert_eq!(bitfield.bits(), 0b0110);
+        assert_eq!(bitfield.get(TestDerived::FOUR), Derived4::Bar);
+        assert_eq!(bitfield.get(TestDerived::THREE), Derived3::Baz);
+    }
+
+    #[test]
+    #[cfg(feature = "derive")]
+    fn derive_from_bits_sparse() {
+        // `BITS` is determined by the largest discriminant, not the number of
+        // variants.
+        assert_eq!(<DerivedSparse as FromBits<u8>>::BITS, 3);
+
+        for (bits, variant) in [
+            (0, DerivedSparse::Foo),
+            (3, DerivedSparse::Bar),
+            (7, DerivedSparse::Baz),
+        ] {
+            assert_eq!(
+                <DerivedSparse as FromBits<u32>>::try_from_bits(bits),
+                Ok(variant)
+            );
+        }
+
+        for bits in [0b001, 0b010, 0b100, 0b101, 0b110] {
+            let err = <DerivedSparse as FromBits<u32>>::try_from_bits(bits).unwrap_err();
+            assert_eq!(
+                err,
+                crate::UnknownVariant::new("DerivedSparse", bits as u128)
+            );
+        }
+    }
 }
@@ -0,0 +1,493 @@
+//! Formatting helpers used by the code generated by the
+//! [`bitfield!`](crate::bitfield!) macro.
+use core::{cmp, fmt};
+
+/// Formats the value of a bitfield's field using its [`fmt::Debug`]
+/// implementation, or the field's raw bits, as `Err(0b...)`, if they are not a
+/// valid bit pattern for the field's type.
+///
+/// The generated `Debug` and `Display` implementations and `record_fields`
+/// method all format fields using this type, so that formatting a bitfield
+/// never panics.
+#[doc(hidden)]
+pub struct FieldValue<T> {
+    value: Option<T>,
+    raw: u128,
+}
+
+impl<T> FieldValue<T> {
+    /// Returns a new `FieldValue` for a field containing the raw bits `raw`,
+    /// which were converted into `value`, or `None` if they were invalid.
+    pub fn new(value: Option<T>, raw: u128) -> Self {
+        Self { value, raw }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for FieldValue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.value {
+            Some(ref value) => fmt::Debug::fmt(value, f),
+            None => write!(f, "Err({:#b})", self.raw),
+        }
+    }
+}
+
+/// Formats a bitfield as a diagram of its bits and fields, for the `Display`
+/// implementation generated by the [`bitfield!`](crate::bitfield!) macro.
+///
+/// Each field is described by its entries in `names`, `masks`, `notes`, and
+/// `values`, which are in the order the fields were declared in.
+#[doc(hidden)]
+pub struct Diagram<'a> {
+    /// The name of the bitfield type.
+    pub name: &'static str,
+    /// The bitfield's raw bits.
+    pub bits: u128,
+    /// The number of bits in the bitfield's integer type.
+    pub width: u32,
+    /// The name of each field.
+    pub names: &'a [&'static str],
+    /// The raw mask of each field.
+    pub masks: &'a [u128],
+    /// The annotation on each field, or an empty string.
+    pub notes: &'a [&'static str],
+    /// The value of each field.
+    pub values: &'a [&'a dyn fmt::Debug],
+}
+
+impl fmt::Display for Diagram<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return self.fmt_compact(f);
+        }
+        if let Some(group) = f.precision() {
+            return self.fmt_grouped(f, group);
+        }
+        self.fmt_diagram(f)
+    }
+}
+
+impl Diagram<'_> {
+    /// Compact, single-line format.
+    fn fmt_compact(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name)?;
+        let mut sep = " { ";
+        for (name, value) in self.names.iter().zip(self.values) {
+            if !name.starts_with('_') {
+                write!(f, "{}{}: {:?}", sep, name, value)?;
+                sep = ", ";
+            }
+        }
+        if sep == " { " {
+            f.write_str(" {}")
+        } else {
+            f.write_str(" }")
+        }
+    }
+
+    /// Grouped format: the diagram is split into rows of `group` bits
+    /// (rounded up to a whole number of bytes), with a separator between
+    /// each byte. A field which spans multiple rows is labeled in each of
+    /// them.
+    fn fmt_grouped(&self, f: &mut fmt::Formatter<'_>, group: usize) -> fmt::Result {
+        let group = (cmp::min(group, self.width as usize) as u32 + 7) & !7;
+        let group = cmp::max(group, 8);
+        let unknown = if f.sign_plus() { "?" } else { " " };
+
+        let mut hi = self.width;
+        while hi > 0 {
+            let lo = hi.saturating_sub(group);
+            let window = (u128::MAX >> (u128::BITS - (hi - lo))) << lo;
+            // Returns the bits of the field at `index` which are in this row.
+            // Reserved fields are never labeled, so they have no bits.
+            let row_mask = |index: usize| {
+                if self.is_reserved(index) {
+                    0
+                } else {
+                    self.masks[index] & window
+                }
+            };
+            // Returns the column at which bit `pos` is drawn.
+            let col = |pos: u32| {
+                let i = (hi - 1 - pos) as usize;
+                i + i / 8
+            };
+            // Returns the bit drawn at column `col`, or `None` if `col` is a
+            // separator or past the end of the row.
+            let pos_at = |col: usize| {
+                if col % 9 == 8 {
+                    return None;
+                }
+                let i = (col / 9 * 8 + col % 9) as u32;
+                (hi - 1).checked_sub(i).filter(|&pos| pos >= lo)
+            };
+
+            if hi != self.width {
+                f.write_str("\n")?;
+            }
+            f.pad("")?;
+            for pos in (lo..hi).rev() {
+                write!(f, "{}", (self.bits >> pos) & 1)?;
+                if pos != lo && pos % 8 == 0 {
+                    f.write_str(" ")?;
+                }
+            }
+            f.write_str("\n")?;
+
+            f.pad("")?;
+            let last = if f.sign_plus() {
+                lo
+            } else {
+                (0..self.masks.len())
+                    .map(row_mask)
+                    .filter(|&mask| mask != 0)
+                    .map(|mask| mask.trailing_zeros())
+                    .min()
+                    .unwrap_or(hi)
+            };
+            for pos in (last..hi).rev() {
+                let mask = self.field_at(pos).map(row_mask);
+                f.write_str(match mask {
+                    None => unknown,
+                    Some(0) => " ",
+                    Some(mask) => glyph(mask, pos),
+                })?;
+                if pos != last && pos % 8 == 0 {
+                    // Ranges of bits which cross a separator are joined
+                    // across it.
+                    let joined = matches!(mask, Some(mask) if mask & (1 << (pos - 1)) != 0);
+                    f.write_str(if joined { "─" } else { " " })?;
+                }
+            }
+            f.write_str("\n")?;
+
+            // Labels are aligned just past the rightmost line in the row, and
+            // written from right to left, so that the lines to labels which
+            // have not yet been written pass through the lines to previous
+            // labels.
+            let max_len = (0..self.masks.len())
+                .filter(|&i| row_mask(i) != 0)
+                .map(|i| self.names[i].len())
+                .max()
+                .unwrap_or(0);
+            let end = (lo..hi)
+                .filter(|&pos| {
+                    self.field_at(pos)
+                        .map_or(false, |i| is_stem(row_mask(i), pos))
+                })
+                .map(col)
+                .max()
+                .unwrap_or(0);
+            for stem in lo..hi {
+                let index = match self.field_at(stem) {
+                    Some(i) if row_mask(i) != 0 && main_stem(row_mask(i)) == stem => i,
+                    _ => continue,
+                };
+                // Returns `true` if a line to a label which has not yet been
+                // written passes through bit `pos`.
+                let pending = |pos: u32| match self.field_at(pos) {
+                    Some(i) if i != index => {
+                        let mask = row_mask(i);
+                        mask != 0 && is_stem(mask, pos) && main_stem(mask) > stem
+                    }
+                    _ => false,
+                };
+
+                f.pad("")?;
+                let len = (end - col(stem)) + (max_len - self.names[index].len());
+                write_leader(f, col(stem), len, pos_at, row_mask(index), pending)?;
+                self.write_label(f, index)?;
+            }
+
+            hi = lo;
+        }
+        Ok(())
+    }
+
+    /// The default format, with one row of bits.
+    fn fmt_diagram(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // If a width narrower than the bitfield is given, only the
+        // `width - 1` least-significant bits are drawn, following an
+        // ellipsis, and only fields which fit in them are labeled. Otherwise,
+        // the width indents each line of the diagram.
+        let top = match f.width() {
+            Some(width) if width < self.width as usize => width.saturating_sub(1) as u32,
+            _ => self.width,
+        };
+        let truncated = top < self.width;
+        let indent = |f: &mut fmt::Formatter<'_>| {
+            if truncated {
+                f.write_str(" ")
+            } else {
+                f.pad("")
+            }
+        };
+        // Returns `true` if the field at `index` is labeled in the diagram.
+        let shown = |index: usize| {
+            !self.is_reserved(index) && most_significant_index(self.masks[index]) <= top
+        };
+
+        if truncated {
+            f.write_str("…")?;
+            for pos in (0..top).rev() {
+                write!(f, "{}", (self.bits >> pos) & 1)?;
+            }
+            f.write_str("\n")?;
+        } else {
+            f.pad("")?;
+            writeln!(f, "{:0width$b}", self.bits, width = self.width as usize)?;
+        }
+
+        indent(f)?;
+        // If the `+` flag is set, mark bits which are not part of any
+        // declared field. Otherwise, bits less significant than every field
+        // are not written.
+        let unknown = if f.sign_plus() { "?" } else { " " };
+        let lowest = self
+            .masks
+            .iter()
+            .map(|mask| mask.trailing_zeros())
+            .min()
+            .unwrap_or(0);
+        let last = if f.sign_plus() { 0 } else { lowest };
+        let mut max_len = 0;
+        for pos in (last..top).rev() {
+            let index = match self.field_at(pos) {
+                Some(index) => index,
+                None => {
+                    f.write_str(unknown)?;
+                    continue;
+                }
+            };
+            if !shown(index) {
+                f.write_str(" ")?;
+                continue;
+            }
+            max_len = cmp::max(max_len, self.names[index].len());
+            f.write_str(glyph(self.masks[index], pos))?;
+        }
+
+        f.write_str("\n")?;
+
+        // Labels are aligned just past the line to the least-significant
+        // field's label, or past the lines to every range of a split field,
+        // whichever is further right.
+        let mut rem = match (0..self.masks.len()).min_by_key(|&i| self.masks[i].trailing_zeros()) {
+            Some(i) if shown(i) => self.masks[i].trailing_zeros() + 1,
+            _ => 0,
+        };
+        for (index, &mask) in self.masks.iter().enumerate() {
+            if !shown(index) || !is_split(mask) {
+                continue;
+            }
+            let (top_lsb, _) = range_at(mask, most_significant_index(mask) - 1);
+            for pos in 0..top_lsb {
+                if is_stem(mask, pos) {
+                    rem = cmp::min(rem, pos);
+                }
+            }
+        }
+
+        // Returns the bit drawn at column `col`. Lines to labels are not
+        // joined or crossed past the start of the labels, at bit `rem`.
+        let pos_at = |col: usize| (top - 1).checked_sub(col as u32).filter(|&pos| pos >= rem);
+        for (index, &mask) in self.masks.iter().enumerate() {
+            if !shown(index) {
+                continue;
+            }
+            // Returns `true` if a line to a field whose label has not yet
+            // been written passes through bit `pos`.
+            let pending = |pos: u32| match self.field_at(pos) {
+                Some(i) if i > index => shown(i) && is_stem(self.masks[i], pos),
+                _ => false,
+            };
+
+            // The label is connected to the field's most significant range of
+            // bits, and the field's other ranges (if it is split) join the
+            // label's line.
+            indent(f)?;
+            let stem = main_stem(mask);
+            let len =
+                (stem as usize + (max_len - self.names[index].len())).saturating_sub(rem as usize);
+            write_leader(f, (top - 1 - stem) as usize, len, pos_at, mask, pending)?;
+            self.write_label(f, index)?;
+        }
+
+        let hidden = (0..self.masks.len())
+            .filter(|&i| !self.is_reserved(i) && !shown(i))
+            .count();
+        if hidden > 0 {
+            indent(f)?;
+            writeln!(f, "(+{} more)", hidden)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the label of the field at `index`, following the line to it.
+    fn write_label(&self, f: &mut fmt::Formatter<'_>, index: usize) -> fmt::Result {
+        let mask = self.masks[index];
+        write!(f, " {}: {:?} (", self.names[index], self.values[index])?;
+        let note = self.notes[index];
+        if !note.is_empty() {
+            write!(f, "{}, ", note)?;
+        }
+        write!(
+            f,
+            "{:0width$b})",
+            extract(self.bits, mask),
+            width = mask.count_ones() as usize
+        )?;
+        if f.sign_minus() {
+            self.write_msb0(f, mask)?;
+        }
+        f.write_str("\n")
+    }
+
+    /// Writes the ranges of bits set in `mask`, numbered MSB-first (bit 0 is
+    /// the most-significant bit), for the `-` flag.
+    fn write_msb0(&self, f: &mut fmt::Formatter<'_>, mask: u128) -> fmt::Result {
+        let mut sep = " [";
+        let mut pos = self.width;
+        while pos > 0 {
+            pos -= 1;
+            if mask & (1 << pos) == 0 {
+                continue;
+            }
+            let (lsb, msb) = range_at(mask, pos);
+            let first = self.width - 1 - msb;
+            let last = self.width - 1 - lsb;
+            if first == last {
+                write!(f, "{}{}", sep, first)?;
+            } else {
+                write!(f, "{}{}:{}", sep, first, last)?;
+            }
+            sep = ", ";
+            pos = lsb;
+        }
+        f.write_str("]")
+    }
+
+    /// Returns the index of the field containing bit `pos`, if there is one.
+    fn field_at(&self, pos: u32) -> Option<usize> {
+        self.masks.iter().position(|mask| mask & (1 << pos) != 0)
+    }
+
+    /// Returns `true` if the field at `index` is reserved, and is therefore
+    /// never labeled.
+    fn is_reserved(&self, index: usize) -> bool {
+        self.names[index].starts_with('_')
+    }
+}
+
+/// Writes the line from the bit drawn at column `stem` to its label, which
+/// ends `len` columns to the right of it.
+///
+/// `pos_at` returns the bit drawn at a column, if there is one. The line is
+/// joined by any other ranges of bits in `mask` that it passes under, and
+/// crosses the lines to any labels which have not yet been written, for
+/// which `pending` returns `true`.
+fn write_leader(
+    f: &mut fmt::Formatter<'_>,
+    stem: usize,
+    len: usize,
+    pos_at: impl Fn(usize) -> Option<u32>,
+    mask: u128,
+    pending: impl Fn(u32) -> bool,
+) -> fmt::Result {
+    for col in 0..stem {
+        f.write_str(match pos_at(col) {
+            Some(pos) if pending(pos) => "│",
+            _ => " ",
+        })?;
+    }
+    f.write_str("└")?;
+    for col in stem + 1..=stem + len {
+        f.write_str(match pos_at(col) {
+            Some(pos) if is_stem(mask, pos) => "┴",
+            Some(pos) if pending(pos) => "┼",
+            _ => "─",
+        })?;
+    }
+    Ok(())
+}
+
+/// Returns the glyph drawn below bit `pos` of the range of bits set in `mask`
+/// which contains it.
+fn glyph(mask: u128, pos: u32) -> &'static str {
+    let (lsb, msb) = range_at(mask, pos);
+    match msb - lsb {
+        0 => "│",
+        _ if pos == msb => "└",
+        1 => "┤",
+        _ if pos == msb - 1 => "┬",
+        _ if pos == lsb => "┘",
+        _ => "─",
+    }
+}
+
+/// Returns the least- and most-significant bit of the contiguous range of bits
+/// set in `mask` that contains bit `pos`. A split field has more than one such
+/// range.
+fn range_at(mask: u128, pos: u32) -> (u32, u32) {
+    let mut lsb = pos;
+    while lsb > 0 && mask & (1 << (lsb - 1)) != 0 {
+        lsb -= 1;
+    }
+    let mut msb = pos;
+    while msb + 1 < u128::BITS && mask & (1 << (msb + 1)) != 0 {
+        msb += 1;
+    }
+    (lsb, msb)
+}
+
+/// Returns `true` if bit `pos` is where the line connecting the range of bits
+/// in `mask` containing `pos` to its label is drawn.
+fn is_stem(mask: u128, pos: u32) -> bool {
+    if mask & (1 << pos) == 0 {
+        return false;
+    }
+    let (lsb, msb) = range_at(mask, pos);
+    pos == if msb == lsb { msb } else { msb - 1 }
+}
+
+/// Returns the bit in the most-significant range of bits set in `mask` where
+/// the line to its label is drawn.
+fn main_stem(mask: u128) -> u32 {
+    let (lsb, msb) = range_at(mask, most_significant_index(mask) - 1);
+    if lsb == msb {
+        msb
+    } else {
+        msb - 1
+    }
+}
+
+/// Returns the index one past the most-significant bit set in `mask`, as a
+/// packing spec's `most_significant_index` does.
+fn most_significant_index(mask: u128) -> u32 {
+    u128::BITS - mask.leading_zeros()
+}
+
+/// Returns `true` if the bits set in `mask` are not contiguous.
+fn is_split(mask: u128) -> bool {
+    if mask == 0 {
+        return false;
+    }
+    let mask = mask >> mask.trailing_zeros();
+    mask & mask.wrapping_add(1) != 0
+}
+
+/// Gathers the bits in `bits` which are set in `mask` into the
+/// least-significant bits of the result, as a packing spec's `unpack_bits`
+/// does.
+fn extract(bits: u128, mut mask: u128) -> u128 {
+    let mut value = 0;
+    let mut packed = 0;
+    while mask != 0 {
+        let pos = mask.trailing_zeros();
+        value |= ((bits >> pos) & 1) << packed;
+        packed += 1;
+        mask &= mask - 1;
+    }
+    value
+}
@@ -4,11 +4,49 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg, doc_cfg_hide))]
 #![cfg_attr(docsrs, doc(cfg_hide(docsrs)))]
 #![cfg_attr(trace_macros, feature(trace_macros))]
-use core::{convert::Infallible, fmt};
+use core::{
+    char::CharTryFromError,
+    cmp::Ordering,
+    convert::Infallible,
+    fmt,
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+        NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, TryFromIntError,
+    },
+};
 
 pub mod pack;
 pub use self::pack::*;
 mod bitfield;
+mod format;
+
+#[doc(hidden)]
+pub use self::format::{Diagram as __Diagram, FieldValue as __FieldValue};
+
+/// Derives an implementation of the [`FromBits`] trait for a fieldless
+/// `#[repr(uN)]` enum.
+///
+/// See the [`FromBits` trait documentation](trait@FromBits#deriving-frombits)
+/// for details.
+#[cfg(feature = "derive")]
+pub use mycelium_bitfield_derive::FromBits;
+
+// Allows the code generated by `#[derive(FromBits)]`, which refers to this
+// crate as `::mycelium_bitfield`, to be used within this crate's tests.
+#[cfg(all(test, feature = "derive"))]
+extern crate self as mycelium_bitfield;
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde as __serde;
+
+#[cfg(feature = "arbitrary")]
+#[doc(hidden)]
+pub use arbitrary as __arbitrary;
+
+#[cfg(feature = "tracing")]
+#[doc(hidden)]
+pub use tracing_core as __tracing_core;
 
 /// An example of the code generated by the [`bitfield!`] macro.
 ///
@@ -34,6 +72,48 @@ mod bitfield;
 pub mod example;
 
 /// Trait implemented by values which can be converted to and from raw bits.
+///
+/// # Deriving `FromBits`
+///
+/// When the "derive" feature flag is enabled, `FromBits` can be derived for
+/// fieldless enums with a `#[repr(...)]` attribute with a primitive integer
+/// type. The derived implementation:
+///
+/// - sets [`Self::BITS`] to the smallest number of bits that can represent
+///   every variant's discriminant,
+/// - converts bits to the variant with the matching discriminant, returning an
+///   [`UnknownVariant`] error if there is no such variant,
+/// - and converts variants into bits by casting them to their discriminant.
+///
+/// ```
+/// # #[cfg(feature = "derive")] {
+/// use mycelium_bitfield::{bitfield, FromBits};
+///
+/// #[repr(u8)]
+/// #[derive(Debug, Eq, PartialEq, FromBits)]
+/// enum MyEnum {
+///     Foo,
+///     Bar,
+///     Baz,
+/// }
+///
+/// // Two bits are required to represent all three variants.
+/// assert_eq!(<MyEnum as FromBits<u32>>::BITS, 2);
+///
+/// bitfield! {
+///     struct MyBitfield<u32> {
+///         const ENUM: MyEnum;
+///         const REST = ..;
+///     }
+/// }
+///
+/// let bitfield = MyBitfield::new().with(MyBitfield::ENUM, MyEnum::Baz);
+/// assert_eq!(bitfield.get(MyBitfield::ENUM), MyEnum::Baz);
+///
+/// // There is no `MyEnum` variant for 0b11.
+/// assert!(MyBitfield::from_bits(0b11).try_get(MyBitfield::ENUM).is_err());
+/// # }
+/// ```
 pub trait FromBits<B>: Sized {
     /// The error type returned by [`Self::try_from_bits`] when an invalid bit
     /// pattern is encountered.
@@ -63,6 +143,267 @@ pub trait FromBits<B>: Sized {
     fn into_bits(self) -> B;
 }
 
+/// Trait implemented by every bitfield type generated by the [`bitfield!`]
+/// macro.
+///
+/// The `bits` and `from_bits` methods are also generated as inherent methods
+/// on each bitfield type. This trait allows code to be generic over multiple
+/// bitfield types.
+///
+/// # Examples
+///
+/// ```
+/// use mycelium_bitfield::{bitfield, BitfieldExt};
+///
+/// bitfield! {
+///     struct Flags<u8> {
+///         const ENABLED: bool;
+///         const MODE = 2;
+///     }
+/// }
+///
+/// bitfield! {
+///     struct Wide<u32> {
+///         const VALUE = 16;
+///     }
+/// }
+///
+/// fn round_trip<B: BitfieldExt>(bitfield: &B) -> B {
+///     B::from_bits(bitfield.bits())
+/// }
+///
+/// let flags = Flags::new().with(Flags::MODE, 0b10);
+/// assert_eq!(round_trip(&flags).bits(), flags.bits());
+///
+/// let wide = Wide::new().with(Wide::VALUE, 0xface);
+/// assert_eq!(round_trip(&wide).bits(), 0xface);
+/// ```
+pub trait BitfieldExt: Copy {
+    /// The integer type which represents this bitfield.
+    type Repr: Copy;
+
+    /// Returns the raw bits of this bitfield.
+    fn bits(&self) -> Self::Repr;
+
+    /// Converts `bits` into an instance of this bitfield type.
+    fn from_bits(bits: Self::Repr) -> Self;
+}
+
+/// Error returned by a bitfield's `checked_with` method when the bit
+/// representation of a value is too wide to fit in the field it is being
+/// packed into.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct OutOfRange {
+    width: u32,
+    value_bits: u32,
+}
+
+impl OutOfRange {
+    /// Returns a new `OutOfRange` error for a field that is `width` bits
+    /// wide, and a value whose bit representation requires `value_bits` bits.
+    pub const fn new(width: u32, value_bits: u32) -> Self {
+        Self { width, value_bits }
+    }
+
+    /// Returns the width, in bits, of the field the value was packed into.
+    pub const fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the number of bits required to represent the offending value.
+    pub const fn value_bits(&self) -> u32 {
+        self.value_bits
+    }
+}
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "value requires {} bits, but the field is only {} bits wide",
+            self.value_bits, self.width
+        )
+    }
+}
+
+/// Error returned by a bitfield's [`TryFrom`] implementation when a field
+/// does not contain a valid bit pattern for that field's type.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct InvalidField {
+    field: &'static str,
+}
+
+impl InvalidField {
+    /// Returns a new `InvalidField` error for the field named `field`.
+    pub const fn new(field: &'static str) -> Self {
+        Self { field }
+    }
+
+    /// Returns the name of the field that contained an invalid bit pattern.
+    pub const fn field(&self) -> &'static str {
+        self.field
+    }
+}
+
+impl fmt::Display for InvalidField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid bit pattern for field `{}`", self.field)
+    }
+}
+
+/// Error returned by a [derived](trait@FromBits#deriving-frombits)
+/// [`FromBits`] implementation when a bit pattern does not match any variant
+/// of an enum.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct UnknownVariant {
+    ty: &'static str,
+    bits: u128,
+}
+
+impl UnknownVariant {
+    /// Returns a new `UnknownVariant` error for the bit pattern `bits`, which
+    /// does not match any variant of the type named `ty`.
+    pub const fn new(ty: &'static str, bits: u128) -> Self {
+        Self { ty, bits }
+    }
+
+    /// Returns the name of the type that the bits were converted into.
+    pub const fn type_name(&self) -> &'static str {
+        self.ty
+    }
+
+    /// Returns the bit pattern that did not match any variant.
+    pub const fn bits(&self) -> u128 {
+        self.bits
+    }
+}
+
+impl fmt::Display for UnknownVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid bit pattern {:#b} for `{}`: no variant has this discriminant",
+            self.bits, self.ty
+        )
+    }
+}
+
+/// Error returned by the [`FromBits`] implementation for a tuple `(A, B)`
+/// when one of the tuple's elements does not contain a valid bit pattern.
+///
+/// If both elements are invalid, the error for the first element is
+/// returned.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TupleError<A, B> {
+    /// The first element of the tuple was invalid.
+    First(A),
+    /// The second element of the tuple was invalid.
+    Second(B),
+}
+
+impl<A: fmt::Display, B: fmt::Display> fmt::Display for TupleError<A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::First(error) => write!(f, "invalid first tuple element: {}", error),
+            Self::Second(error) => write!(f, "invalid second tuple element: {}", error),
+        }
+    }
+}
+
+/// A count of ticks of a clock which ticks `HZ` times per second, packed as a
+/// `T`.
+///
+/// `Ticks` packs into the same number of bits as `T`, and unpacks to a value
+/// which can be converted into the [`Duration`] it represents. Its
+/// [`fmt::Debug`] implementation prints that duration, so that fields storing
+/// tick counts, such as in timer configuration registers, are self-describing
+/// when a bitfield is formatted.
+///
+/// `HZ` must not be zero.
+///
+/// # Examples
+///
+/// ```
+/// use core::time::Duration;
+/// use mycelium_bitfield::{bitfield, Ticks};
+///
+/// bitfield! {
+///     struct TimerConfig<u32> {
+///         const ENABLED: bool;
+///         /// The timer period, in ticks of a 1 kHz clock.
+///         const PERIOD: Ticks<1_000, u16>;
+///     }
+/// }
+///
+/// let config = TimerConfig::new()
+///     .with(TimerConfig::ENABLED, true)
+///     .with(TimerConfig::PERIOD, Ticks::new(1_500));
+///
+/// let period = config.get(TimerConfig::PERIOD);
+/// assert_eq!(period.count(), 1_500);
+/// assert_eq!(period.duration(), Duration::from_millis(1_500));
+/// assert_eq!(format!("{:?}", period), "1.5s");
+/// ```
+///
+/// [`Duration`]: core::time::Duration
+#[derive(Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Ticks<const HZ: u64, T = u32>(T);
+
+impl<const HZ: u64, T> Ticks<HZ, T> {
+    /// The number of ticks per second.
+    pub const HZ: u64 = HZ;
+
+    /// Returns a new `Ticks` for `count` ticks.
+    pub const fn new(count: T) -> Self {
+        Self(count)
+    }
+
+    /// Returns the number of ticks.
+    pub fn count(self) -> T {
+        self.0
+    }
+}
+
+impl<const HZ: u64, T: Into<u64>> Ticks<HZ, T> {
+    /// Returns the [`Duration`] of this many ticks of a clock which ticks
+    /// `HZ` times per second.
+    ///
+    /// # Panics
+    ///
+    /// If `HZ` is zero.
+    ///
+    /// [`Duration`]: core::time::Duration
+    pub fn duration(self) -> core::time::Duration {
+        let count = self.0.into();
+        let secs = count / HZ;
+        let nanos = u128::from(count % HZ) * 1_000_000_000 / u128::from(HZ);
+        core::time::Duration::new(secs, nanos as u32)
+    }
+}
+
+impl<const HZ: u64, T: Copy + Into<u64>> fmt::Debug for Ticks<HZ, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.duration(), f)
+    }
+}
+
+/// Packs a tick count into the same number of bits as `T`.
+impl<B, T, const HZ: u64> FromBits<B> for Ticks<HZ, T>
+where
+    T: FromBits<B>,
+{
+    const BITS: u32 = T::BITS;
+    type Error = T::Error;
+
+    fn try_from_bits(bits: B) -> Result<Self, Self::Error> {
+        T::try_from_bits(bits).map(Self)
+    }
+
+    fn into_bits(self) -> B {
+        self.0.into_bits()
+    }
+}
+
 macro_rules! impl_frombits_for_ty {
    ($(impl FromBits<$($F:ty),+> for $T:ty {})+) => {
         $(
@@ -77,7 +418,10 @@ macro_rules! impl_frombits_for_ty {
                     }
 
                     fn into_bits(self) -> $F {
-                        self as $F
+                        // Mask off any bits beyond `Self::BITS`, so that
+                        // negative signed values are zero-extended rather than
+                        // sign-extended.
+                        (self as $F) & (<$F>::MAX >> (<$F>::BITS - Self::BITS))
                     }
                 }
             )*
@@ -108,20 +452,272 @@ macro_rules! impl_frombits_for_bool {
     }
 }
 
+macro_rules! impl_frombits_for_bool_array {
+    (impl<const N: usize> FromBits<$($F:ty),+> for [bool; N] {}) => {
+        $(
+            /// Packs an array of `N` flags into `N` consecutive bits, with
+            /// the first element of the array in the least-significant bit.
+            impl<const N: usize> FromBits<$F> for [bool; N] {
+                const BITS: u32 = N as u32;
+                type Error = Infallible;
+
+                fn try_from_bits(f: $F) -> Result<Self, Self::Error> {
+                    let mut flags = [false; N];
+                    for (i, flag) in flags.iter_mut().enumerate() {
+                        *flag = f & (1 << i) != 0;
+                    }
+                    Ok(flags)
+                }
+
+                fn into_bits(self) -> $F {
+                    self.iter()
+                        .enumerate()
+                        .fold(0, |bits, (i, &flag)| bits | ((flag as $F) << i))
+                }
+            }
+        )+
+    }
+}
+
+macro_rules! impl_frombits_for_char {
+    (impl FromBits<$($F:ty),+> for char {}) => {
+        $(
+            impl FromBits<$F> for char {
+                const BITS: u32 = 21;
+                type Error = CharTryFromError;
+
+                fn try_from_bits(f: $F) -> Result<Self, Self::Error> {
+                    // Values too large for a `u32` are not valid `char`s, and
+                    // neither is `u32::MAX`.
+                    let f = u32::try_from(f).unwrap_or(u32::MAX);
+                    char::try_from(f)
+                }
+
+                fn into_bits(self) -> $F {
+                    self as $F
+                }
+            }
+        )+
+    }
+}
+
+macro_rules! impl_frombits_for_ordering {
+    (impl FromBits<$($F:ty),+> for Ordering {}) => {
+        $(
+            /// Packs an [`Ordering`] into 2 bits, with `Less`, `Equal`, and
+            /// `Greater` represented as `0b00`, `0b01`, and `0b10`,
+            /// respectively. Unpacking `0b11` returns an error.
+            impl FromBits<$F> for Ordering {
+                const BITS: u32 = 2;
+                type Error = UnknownVariant;
+
+                fn try_from_bits(f: $F) -> Result<Self, Self::Error> {
+                    match f {
+                        0b00 => Ok(Ordering::Less),
+                        0b01 => Ok(Ordering::Equal),
+                        0b10 => Ok(Ordering::Greater),
+                        bits => Err(UnknownVariant::new("Ordering", bits as u128)),
+                    }
+                }
+
+                fn into_bits(self) -> $F {
+                    match self {
+                        Ordering::Less => 0b00,
+                        Ordering::Equal => 0b01,
+                        Ordering::Greater => 0b10,
+                    }
+                }
+            }
+        )+
+    }
+}
+
+macro_rules! impl_frombits_for_nonzero {
+    ($($NonZero:ty => $T:ty),+ $(,)?) => {
+        $(
+            /// Packs a non-zero integer into the same number of bits as the
+            /// corresponding integer type. Unpacking a zero value returns an
+            /// error.
+            impl<B> FromBits<B> for $NonZero
+            where
+                $T: FromBits<B, Error = Infallible>,
+            {
+                const BITS: u32 = <$T as FromBits<B>>::BITS;
+                type Error = TryFromIntError;
+
+                fn try_from_bits(bits: B) -> Result<Self, Self::Error> {
+                    let value = match <$T as FromBits<B>>::try_from_bits(bits) {
+                        Ok(value) => value,
+                        Err(infallible) => match infallible {},
+                    };
+                    <$NonZero>::try_from(value)
+                }
+
+                fn into_bits(self) -> B {
+                    self.get().into_bits()
+                }
+            }
+        )+
+    }
+}
+
+macro_rules! impl_frombits_for_tuple {
+    (impl<A, B> FromBits<$($F:ty),+> for (A, B) {}) => {
+        $(
+            /// Packs a pair of values into `A::BITS + B::BITS` bits, with the
+            /// first element in the least-significant bits and the second
+            /// element above it.
+            impl<A, B> FromBits<$F> for (A, B)
+            where
+                A: FromBits<$F>,
+                B: FromBits<$F>,
+            {
+                const BITS: u32 = A::BITS + B::BITS;
+                type Error = TupleError<A::Error, B::Error>;
+
+                fn try_from_bits(f: $F) -> Result<Self, Self::Error> {
+                    const fn mask(bits: u32) -> $F {
+                        if bits >= <$F>::BITS {
+                            <$F>::MAX
+                        } else {
+                            (1 << bits) - 1
+                        }
+                    }
+
+                    let a = A::try_from_bits(f & mask(A::BITS)).map_err(TupleError::First)?;
+                    let b = f.checked_shr(A::BITS).unwrap_or(0) & mask(B::BITS);
+                    let b = B::try_from_bits(b).map_err(TupleError::Second)?;
+                    Ok((a, b))
+                }
+
+                fn into_bits(self) -> $F {
+                    let a = self.0.into_bits();
+                    let b = self.1.into_bits().checked_shl(A::BITS).unwrap_or(0);
+                    a | b
+                }
+            }
+        )+
+    }
+}
+
+/// The bit pattern that represents `None` when an `Option` is packed into a
+/// `B`.
+trait NoneBits<B> {
+    const NONE_BITS: B;
+}
+
+macro_rules! impl_frombits_for_option {
+    (impl<T> FromBits<$($F:ty),+> for Option<T> {}) => {
+        $(
+            impl<T: FromBits<$F>> NoneBits<$F> for Option<T> {
+                const NONE_BITS: $F = if T::BITS >= <$F>::BITS {
+                    <$F>::MAX
+                } else {
+                    (1 << T::BITS) - 1
+                };
+            }
+
+            /// Packs an optional value into `T::BITS` bits, using the
+            /// all-ones bit pattern to represent `None`.
+            ///
+            /// Any other bit pattern is unpacked as `Some(T)`. If `T`'s own
+            /// bit representation may be all ones, such a value cannot be
+            /// distinguished from `None`, and is unpacked as `None`.
+            impl<T> FromBits<$F> for Option<T>
+            where
+                T: FromBits<$F>,
+            {
+                const BITS: u32 = T::BITS;
+                type Error = T::Error;
+
+                fn try_from_bits(f: $F) -> Result<Self, Self::Error> {
+                    if f == <Self as NoneBits<$F>>::NONE_BITS {
+                        return Ok(None);
+                    }
+                    T::try_from_bits(f).map(Some)
+                }
+
+                fn into_bits(self) -> $F {
+                    match self {
+                        Some(value) => value.into_bits(),
+                        None => <Self as NoneBits<$F>>::NONE_BITS,
+                    }
+                }
+            }
+        )+
+    }
+}
+
 impl_frombits_for_bool! {
-    impl FromBits<u8, u16, u32, u64, usize> for bool {}
+    impl FromBits<u8, u16, u32, u64, u128, usize> for bool {}
+}
+
+impl_frombits_for_nonzero! {
+    NonZeroU8 => u8,
+    NonZeroU16 => u16,
+    NonZeroU32 => u32,
+    NonZeroU64 => u64,
+    NonZeroU128 => u128,
+    NonZeroUsize => usize,
+    NonZeroI8 => i8,
+    NonZeroI16 => i16,
+    NonZeroI32 => i32,
+    NonZeroI64 => i64,
+    NonZeroI128 => i128,
+    NonZeroIsize => isize,
+}
+
+/// Packs a Unicode scalar value into 21 bits.
+impl FromBits<u32> for char {
+    const BITS: u32 = 21;
+    type Error = CharTryFromError;
+
+    fn try_from_bits(f: u32) -> Result<Self, Self::Error> {
+        char::try_from(f)
+    }
+
+    fn into_bits(self) -> u32 {
+        self as u32
+    }
+}
+
+impl_frombits_for_char! {
+    impl FromBits<u64, u128> for char {}
+}
+
+#[cfg(not(target_pointer_width = "16"))]
+impl_frombits_for_char! {
+    impl FromBits<usize> for char {}
+}
+
+impl_frombits_for_ordering! {
+    impl FromBits<u8, u16, u32, u64, u128, usize> for Ordering {}
+}
+
+impl_frombits_for_option! {
+    impl<T> FromBits<u8, u16, u32, u64, u128, usize> for Option<T> {}
+}
+
+impl_frombits_for_tuple! {
+    impl<A, B> FromBits<u8, u16, u32, u64, u128, usize> for (A, B) {}
+}
+
+impl_frombits_for_bool_array! {
+    impl<const N: usize> FromBits<u8, u16, u32, u64, u128, usize> for [bool; N] {}
 }
 
 impl_frombits_for_ty! {
-    impl FromBits<u8, u16, u32, u64> for u8 {}
-    impl FromBits<u16, u32, u64> for u16 {}
-    impl FromBits<u32, u64> for u32 {}
-    impl FromBits<u64> for u64 {}
+    impl FromBits<u8, u16, u32, u64, u128> for u8 {}
+    impl FromBits<u16, u32, u64, u128> for u16 {}
+    impl FromBits<u32, u64, u128> for u32 {}
+    impl FromBits<u64, u128> for u64 {}
+    impl FromBits<u128> for u128 {}
 
-    impl FromBits<u8, u16, u32, u64> for i8 {}
-    impl FromBits<u16, u32, u64> for i16 {}
-    impl FromBits<u32, u64> for i32 {}
-    impl FromBits<u64> for i64 {}
+    impl FromBits<u8, u16, u32, u64, u128> for i8 {}
+    impl FromBits<u16, u32, u64, u128> for i16 {}
+    impl FromBits<u32, u64, u128> for i32 {}
+    impl FromBits<u64, u128> for i64 {}
+    impl FromBits<u128> for i128 {}
 
     // Rust doesn't support 8 bit targets, so {u,i}size are always at least 16 bit wide,
     // source: https://doc.rust-lang.org/1.45.2/src/core/convert/num.rs.html#134-139
@@ -139,14 +735,14 @@ impl_frombits_for_ty! {
 
 #[cfg(target_pointer_width = "16")]
 impl_frombits_for_ty! {
-    impl FromBits<u16, u32, u64> for usize {}
-    impl FromBits<u16, u32, u64> for isize {}
+    impl FromBits<u16, u32, u64, u128> for usize {}
+    impl FromBits<u16, u32, u64, u128> for isize {}
 }
 
 #[cfg(target_pointer_width = "32")]
 impl_frombits_for_ty! {
-    impl FromBits<u32, u64> for usize {}
-    impl FromBits<u32, u64> for isize {}
+    impl FromBits<u32, u64, u128> for usize {}
+    impl FromBits<u32, u64, u128> for isize {}
 
     impl FromBits<usize> for u32 {}
     impl FromBits<usize> for i32 {}
@@ -154,8 +750,8 @@ impl_frombits_for_ty! {
 
 #[cfg(target_pointer_width = "64")]
 impl_frombits_for_ty! {
-    impl FromBits<u64> for usize {}
-    impl FromBits<u64> for isize {}
+    impl FromBits<u64, u128> for usize {}
+    impl FromBits<u64, u128> for isize {}
 
     impl FromBits<usize> for u32 {}
     impl FromBits<usize> for i32 {}
@@ -6,8 +6,8 @@
 //! The bit packing utilities consist of a type that defines a specification for
 //! a bit range to pack into, and a wrapper type for an unsigned integer
 //! defining methods to pack bit ranges into it. Packing specs are defined for
-//! [`u64`],  [`u32`], [`u16`], and [`u8`], as [`Pack64`], [`Pack32`],
-//! [`Pack16`], and [`Pack8`], respectively.
+//! [`u128`], [`u64`],  [`u32`], [`u16`], and [`u8`], as [`Pack128`],
+//! [`Pack64`], [`Pack32`], [`Pack16`], and [`Pack8`], respectively.
 //!
 //! Note that the bit packing utilities are generic using macros, rather than
 //! using generics and traits, because they are intended to be usable in
@@ -130,6 +130,71 @@
 //! assert_eq!(val, 0xfeedc0ffee00); // starting to detect a bit of a theme here...
 //! ```
 //!
+//! ## Building Packing Specs
+//!
+//! Packing specs are built up using the following `const fn` methods, so that
+//! they can be used for ad-hoc bit manipulation without declaring a whole
+//! [`bitfield!`](crate::bitfield!) type:
+//!
+//! - `least_significant(n)` returns a packing spec for the `n`
+//!   least-significant bits.
+//! - `first()` returns a packing spec for a `T`-typed value in the
+//!   [`T::BITS`](crate::FromBits::BITS) least-significant bits.
+//! - `next(n)` returns a packing spec for the `n` bits immediately following
+//!   an existing spec.
+//! - `then::<T>()` returns a packing spec for a `T`-typed value in the
+//!   [`T::BITS`](crate::FromBits::BITS) bits immediately following an existing
+//!   spec.
+//! - `remaining()` returns a packing spec for _all_ the bits following an
+//!   existing spec.
+//! - `typed::<T>()` changes the type of value packed by an existing spec,
+//!   without changing the bits it covers.
+//!
+//! For example:
+//!
+//! ```
+//! use mycelium_bitfield::Pack32;
+//!
+//! // The 3 least-significant bits.
+//! const KIND: Pack32 = Pack32::least_significant(3);
+//! // Skip the next 4 bits, and pack a `u8` into the 8 bits following them.
+//! const BYTE: Pack32<u8> = KIND.next(4).then::<u8>();
+//! // A `bool` in the next bit.
+//! const FLAG: Pack32<bool> = BYTE.then::<bool>();
+//! // All the remaining bits.
+//! const REST: Pack32 = FLAG.remaining();
+//!
+//! assert_eq!(BYTE.least_significant_index(), 7);
+//! assert_eq!(REST.bits(), 32 - 16);
+//!
+//! let val = KIND.pack(0b101, 0);
+//! let val = BYTE.pack(0xAB, val);
+//! let val = FLAG.pack(true, val);
+//!
+//! assert_eq!(val, (1 << 15) | (0xAB << 7) | 0b101);
+//!
+//! // Typed packing specs unpack values of their type.
+//! assert_eq!(BYTE.unpack(val), 0xABu8);
+//! assert!(FLAG.unpack(val));
+//! // Nothing was packed into the remaining bits.
+//! assert_eq!(REST.unpack_bits(val), 0);
+//! ```
+//!
+//! Because these methods are `const fn`s, the layout of a chain of specs can
+//! also be checked at compile time:
+//!
+//! ```
+//! use mycelium_bitfield::Pack16;
+//!
+//! const LOW: Pack16<u8> = Pack16::first();
+//! const FLAG: Pack16<bool> = LOW.then::<bool>();
+//! const HIGH: Pack16 = FLAG.remaining();
+//!
+//! const _: () = assert!(FLAG.offset() == 8);
+//! const _: () = assert!(HIGH.offset() == 9);
+//! const _: () = assert!(HIGH.width() == 7);
+//! ```
+//!
 use super::FromBits;
 use core::{
     any::type_name,
@@ -296,7 +361,18 @@ macro_rules! make_packers {
                     Self::SIZE_BITS - self.mask.leading_zeros()
                 }
 
-                #[doc(hidden)]
+                /// Returns a packing spec for the same bits as `self`, but
+                /// packing `T2`-typed values.
+                ///
+                /// This is useful for converting a typed packing spec back
+                /// into an untyped one, such as when passing a set of specs
+                /// to [`assert_all_valid`](Self::assert_all_valid).
+                ///
+                /// # Panics
+                ///
+                /// If `self` packs more than [`T2::BITS`](crate::FromBits::BITS)
+                /// bits. When called in a `const` context, this is a
+                /// compile-time error instead.
                 pub const fn typed<T2, F2>(self) -> $Pack<T2, F2>
                 where
                     T2: FromBits<$Bits>
@@ -312,9 +388,63 @@ macro_rules! make_packers {
 
                 /// Returns the number of bits needed to pack this value.
                 pub const fn bits(&self) -> u32 {
+                    if self.is_split() {
+                        return self.mask.count_ones();
+                    }
                     Self::SIZE_BITS - (self.mask >> self.shift).leading_zeros()
                 }
 
+                /// Returns `true` if the bits packed by this packing spec are
+                /// not contiguous, i.e. if it was constructed by
+                /// [`join`](Self::join)ing ranges separated by a gap.
+                #[inline]
+                pub const fn is_split(&self) -> bool {
+                    if self.mask == 0 {
+                        return false;
+                    }
+                    let mask = self.mask >> self.mask.trailing_zeros();
+                    mask & mask.wrapping_add(1) != 0
+                }
+
+                /// Returns a packing spec which packs values into the bits of
+                /// both `self` and `other`, which need not be contiguous.
+                ///
+                /// The resulting packing spec packs the least-significant bits
+                /// of a value into its least-significant range of bits, and
+                /// the remaining bits into each more-significant range in
+                /// turn. This is useful for values which a register splits
+                /// across multiple ranges separated by other fields. `self`
+                /// and `other` should not overlap.
+                ///
+                /// Note that a split packing spec cannot be used to construct
+                #[doc = concat!("a [`", stringify!($Pair), "`].")]
+                pub const fn join(&self, other: &Self) -> Self {
+                    let shift = if self.shift < other.shift { self.shift } else { other.shift };
+                    Self {
+                        mask: self.mask | other.mask,
+                        shift,
+                        _dst_ty: PhantomData,
+                    }
+                }
+
+                /// Returns the offset of this packing spec: the index of the
+                /// least-significant bit in the packed range.
+                ///
+                /// This is equivalent to
+                /// [`least_significant_index`](Self::least_significant_index).
+                #[inline]
+                pub const fn offset(&self) -> u32 {
+                    self.shift
+                }
+
+                /// Returns the width of this packing spec, in bits.
+                ///
+                /// This is equivalent to [`bits`](Self::bits).
+                #[inline]
+                pub const fn width(&self) -> u32 {
+                    self.bits()
+                }
+
                 /// Returns the maximum value of this packing spec (i.e. a value
                 /// with all the bits set)
                 pub const fn max_value(&self) -> $Bits {
@@ -333,6 +463,22 @@ macro_rules! make_packers {
                     self.mask
                 }
 
+                /// Returns a mask with the bits in this packing spec's range
+                /// set, shifted into position.
+                ///
+                /// For a contiguous packing spec, this is equal to
+                /// [`max_value`](Self::max_value) shifted left by
+                /// [`offset`](Self::offset). For a [split](Self::is_split)
+                /// packing spec, only the bits in each of its ranges are set,
+                /// and the bits in the gaps between them are clear. This is
+                /// useful for performing read-modify-write operations on a
+                /// register manually, and is equivalent to
+                /// [`raw_mask`](Self::raw_mask).
+                #[inline]
+                pub const fn mask(&self) -> $Bits {
+                    self.mask
+                }
+
                 /// Pack the [`self.bits()`] least-significant bits from `value` into `base`.
                 ///
                 /// Any bits more significant than the [`self.bits()`]-th bit are ignored.
@@ -343,6 +489,9 @@ macro_rules! make_packers {
                     let value = value & self.max_value();
                     // other bits from `base` we don't want to touch
                     let rest = base & !self.mask;
+                    if self.is_split() {
+                        return rest | self.deposit(value);
+                    }
                     rest | (value << self.shift)
                 }
 
@@ -356,7 +505,11 @@ macro_rules! make_packers {
                 pub fn pack_into_truncating<'base>(&self, value: $Bits, base: &'base mut $Bits) -> &'base mut $Bits {
                     let value = value & self.max_value();
                     *base &= !self.mask;
-                    *base |= (value << self.shift);
+                    if self.is_split() {
+                        *base |= self.deposit(value);
+                    } else {
+                        *base |= (value << self.shift);
+                    }
                     base
                 }
 
@@ -444,9 +597,90 @@ macro_rules! make_packers {
                 /// Unpack this packer's bits from `source`.
                 #[inline]
                 pub const fn unpack_bits(&self, src: $Bits) -> $Bits {
+                    if self.is_split() {
+                        return self.extract(src);
+                    }
                     (src & self.mask) >> self.shift
                 }
 
+                /// Scatters the least-significant bits of `value` into each
+                /// contiguous range of a split packing spec's mask, from least-
+                /// to most-significant.
+                const fn deposit(&self, mut value: $Bits) -> $Bits {
+                    let mut mask = self.mask;
+                    let mut bits: $Bits = 0;
+                    while mask != 0 {
+                        let start = mask.trailing_zeros();
+                        let len = (mask >> start).trailing_ones();
+                        let range = Self::mk_mask(len);
+                        bits |= (value & range) << start;
+                        value = value.wrapping_shr(len);
+                        mask &= !(range << start);
+                    }
+                    bits
+                }
+
+                /// Gathers the bits in each contiguous range of a split packing
+                /// spec's mask from `src`. This is the inverse of `deposit`.
+                const fn extract(&self, src: $Bits) -> $Bits {
+                    let mut mask = self.mask;
+                    let mut value: $Bits = 0;
+                    let mut packed = 0;
+                    while mask != 0 {
+                        let start = mask.trailing_zeros();
+                        let len = (mask >> start).trailing_ones();
+                        let range = Self::mk_mask(len);
+                        value |= ((src >> start) & range) << packed;
+                        packed += len;
+                        mask &= !(range << start);
+                    }
+                    value
+                }
+
+
+                /// Unpack this packer's bits from `source`, returning `None` if
+                /// this packing spec is not valid.
+                ///
+                /// Unlike [`unpack_bits`](Self::unpack_bits), which returns
+                /// meaningless bits when given a packing spec that does not
+                /// fit in
+                #[doc = concat!("a [`", stringify!($Bits), "`],")]
+                /// this checks the spec against the width of the backing
+                /// integer type first. This is useful when packing specs are
+                /// composed manually, rather than generated by
+                /// [`bitfield!`](crate::bitfield!). See
+                /// [`is_valid`](Self::is_valid) for details on which packing
+                /// specs are considered valid.
+                #[inline]
+                pub const fn unpack_checked(&self, src: $Bits) -> Option<$Bits> {
+                    if !self.is_valid() {
+                        return None;
+                    }
+                    Some(self.unpack_bits(src))
+                }
+
+                /// Returns `true` if this packing spec is valid.
+                ///
+                /// A packing spec is valid if its range of bits fits within
+                #[doc = concat!("a [`", stringify!($Bits), "`].")]
+                /// This checks the same conditions as
+                /// [`assert_valid`](Self::assert_valid), but returns a `bool`
+                /// rather than panicking, and may be called in a `const`
+                /// context.
+                pub const fn is_valid(&self) -> bool {
+                    if self.shift >= Self::SIZE_BITS
+                        || self.bits() > Self::SIZE_BITS
+                        || self.bits() + self.shift > Self::SIZE_BITS
+                    {
+                        return false;
+                    }
+                    if self.is_split() {
+                        return true;
+                    }
+                    let msb = self.most_significant_index();
+                    let lsb = self.least_significant_index();
+                    msb >= lsb && msb - lsb == self.bits()
+                }
 
                 /// Returns `true` if **any** bits specified by this packing spec
                 /// are set in `src`.
@@ -562,6 +796,11 @@ macro_rules! make_packers {
                         self,
                         cx,
                     );
+                    // The bits of a split packing spec span a wider range
+                    // than the number of bits it packs.
+                    if self.is_split() {
+                        return;
+                    }
                     assert_eq!(self.most_significant_index() - self.least_significant_index(), self.bits(),
                     "most_significant_index - least_significant_index ({} + {} = {}) must equal total number of bits ({})\n\
                     -> while checking validity of {:?}{}",
@@ -583,6 +822,30 @@ macro_rules! make_packers {
                     $Pack::<$Bits, ()>::least_significant(T::BITS).typed()
                 }
 
+                /// Assert that this packing spec is valid for packing and
+                /// unpacking `T`-typed values.
+                ///
+                /// In addition to the assertions made by
+                /// [`assert_valid`](Self::assert_valid), this asserts that
+                /// the number of bits in this packing spec is exactly
+                /// [`T::BITS`](FromBits::BITS). If the packing spec is
+                /// narrower than `T::BITS`, the bit representation of some
+                /// `T`-typed values may not fit in it.
+                #[track_caller]
+                pub fn assert_valid_typed(&self) {
+                    self.assert_valid_inner(&"");
+                    assert_eq!(
+                        self.bits(),
+                        T::BITS,
+                        "packing spec is {} bits wide, but `FromBits::BITS` for {} is {}\n\
+                        -> while checking validity of {:?}",
+                        self.bits(),
+                        type_name::<T>(),
+                        T::BITS,
+                        self,
+                    );
+                }
+
                 /// Returns a pair type for packing bits from the range
                 /// specified by `self` at the specified offset `at`, which may
                 /// differ from `self`'s offset.
@@ -618,15 +881,23 @@ macro_rules! make_packers {
 
                 /// Pack the [`self.bits()`] least-significant bits from `value` into `base`.
                 ///
+                /// Only the bits in `base` within the range of this packing
+                /// spec are modified. Any bits in `value`'s bit representation
+                /// more significant than the [`self.bits()`]-th bit are
+                /// discarded; they are never packed into other bits in `base`.
+                ///
                 /// # Panics
                 ///
-                /// Panics if any other bits outside of [`self.bits()`] are set
-                /// in `value`.
+                /// In debug builds (when `debug_assertions` are enabled), this
+                /// panics if any bits outside of [`self.bits()`] are set in
+                /// `value`. In release builds, those bits are silently
+                /// discarded. To discard out-of-range bits without asserting,
+                /// use [`pack_lossy`](Self::pack_lossy) instead.
                 ///
                 /// [`self.bits()`]: Self::bits
                 pub fn pack(&self, value: T, base: $Bits) -> $Bits {
                     let value = value.into_bits();
-                    assert!(
+                    debug_assert!(
                         value <= self.max_value(),
                         "bits outside of packed range are set!\n     value: {:#b},\n max_value: {:#b}",
                         value,
@@ -635,26 +906,42 @@ macro_rules! make_packers {
                     self.pack_truncating(value, base)
                 }
 
+                /// Pack the [`self.bits()`] least-significant bits from `value`
+                /// into `base`, discarding any more-significant bits.
+                ///
+                /// Unlike [`pack`](Self::pack), this method never asserts that
+                /// `value` fits in this packing spec, even in debug builds.
+                /// This should be used when truncating `value` is the intended
+                /// behavior.
+                ///
+                /// [`self.bits()`]: Self::bits
+                pub fn pack_lossy(&self, value: T, base: $Bits) -> $Bits {
+                    self.pack_truncating(value.into_bits(), base)
+                }
+
                 /// Pack the [`self.bits()`] least-significant bits from `value`
                 /// into `base`, mutating `base`.
                 ///
+                /// This behaves identically to [`pack`](Self::pack), except
+                /// that `base` is mutated in place.
+                ///
                 /// # Panics
                 ///
-                /// Panics if any other bits outside of [`self.bits()`] are set
-                /// in `value`.
+                /// In debug builds (when `debug_assertions` are enabled), this
+                /// panics if any bits outside of [`self.bits()`] are set in
+                /// `value`. In release builds, those bits are silently
+                /// discarded.
                 ///
                 /// [`self.bits()`]: Self::bits
                 pub fn pack_into<'base>(&self, value: T, base: &'base mut $Bits) -> &'base mut $Bits {
                     let value = value.into_bits();
-                    assert!(
+                    debug_assert!(
                         value <= self.max_value(),
                         "bits outside of packed range are set!\n     value: {:#b},\n max_value: {:#b}",
                         value,
                         self.max_value(),
                     );
-                    *base &= !self.mask;
-                    *base |= (value << self.shift);
-                    base
+                    self.pack_into_truncating(value, base)
                 }
 
                 /// Attempts to unpack a `T`-typed value from `src`.
@@ -1041,6 +1328,7 @@ macro_rules! make_packers {
 
 make_packers! {
     pub struct PackUsize { bits: usize, packing: PackingUsize, pair: PairUsize }
+    pub struct Pack128 { bits: u128, packing: Packing128, pair: Pair128, }
     pub struct Pack64 { bits: u64, packing: Packing64, pair: Pair64, }
     pub struct Pack32 { bits: u32, packing: Packing32, pair: Pair32, }
     pub struct Pack16 { bits: u16, packing: Packing16, pair: Pair16, }
@@ -1242,7 +1530,292 @@ mod tests {
         };
     }
 
+    #[test]
+    fn builder() {
+        const KIND: Pack32 = Pack32::least_significant(3);
+        const BYTE: Pack32<u8> = KIND.next(4).then::<u8>();
+        const FLAG: Pack32<bool> = BYTE.then::<bool>();
+        const REST: Pack32 = FLAG.remaining();
+
+        Pack32::assert_all_valid(&[
+            ("KIND", KIND),
+            ("BYTE", BYTE.typed()),
+            ("FLAG", FLAG.typed()),
+            ("REST", REST),
+        ]);
+        assert_eq!(BYTE.offset(), 7);
+        assert_eq!(BYTE.width(), 8);
+        assert_eq!(FLAG.offset(), 15);
+        assert_eq!(REST.offset(), 16);
+        assert_eq!(REST.width(), 16);
+
+        let mut val = 0u32;
+        KIND.pack_into(0b110, &mut val);
+        BYTE.pack_into(0xfe, &mut val);
+        FLAG.pack_into(true, &mut val);
+        REST.pack_into(0xc0ff, &mut val);
+        assert_eq!(val, (0xc0ff << 16) | (1 << 15) | (0xfe << 7) | 0b110);
+
+        assert_eq!(KIND.unpack_bits(val), 0b110);
+        assert_eq!(BYTE.unpack(val), 0xfe);
+        assert!(FLAG.unpack(val));
+        assert_eq!(REST.unpack_bits(val), 0xc0ff);
+    }
+
+    #[test]
+    fn const_chain() {
+        const A: Pack32<u8> = Pack32::first();
+        const B: Pack32<u8> = A.then::<u8>();
+        const C: Pack32<u16> = B.then::<u16>();
+
+        const _: () = assert!(A.offset() == 0);
+        const _: () = assert!(B.offset() == 8);
+        const _: () = assert!(C.offset() == 16);
+        const _: () = assert!(C.width() == 16);
+        const C_UNTYPED: Pack32 = C.typed();
+        const _: () = assert!(C_UNTYPED.raw_mask() == 0xffff_0000);
+
+        assert_eq!(A.offset(), 0);
+        assert_eq!(B.offset(), 8);
+        assert_eq!(C.offset(), 16);
+
+        let val = C.pack(0xc0ff, B.pack(0xee, A.pack(0x42, 0)));
+        assert_eq!(val, 0xc0ff_ee42);
+        assert_eq!(C_UNTYPED.unpack_bits(val), 0xc0ff);
+    }
+
+    #[test]
+    fn pack_lossy() {
+        const LOW: Pack32 = Pack32::least_significant(4);
+        const HIGH: Pack32 = LOW.next(4);
+
+        // Bits outside of the packing spec are discarded, and do not clobber
+        // adjacent bits in the base value.
+        let val = LOW.pack_lossy(0xff, 0);
+        assert_eq!(val, 0xf);
+        let val = HIGH.pack_lossy(0b1_0101, val);
+        assert_eq!(val, 0b0101_1111);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "bits outside of packed range are set!")]
+    fn pack_out_of_range_debug() {
+        Pack32::least_significant(4).pack(0xff, 0);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "bits outside of packed range are set!")]
+    fn pack_into_out_of_range_debug() {
+        let mut val = 0;
+        Pack32::least_significant(4).pack_into(0xff, &mut val);
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    fn pack_out_of_range_release() {
+        const LOW: Pack32 = Pack32::least_significant(4);
+        assert_eq!(LOW.pack(0xff, 0), LOW.pack_lossy(0xff, 0));
+
+        let mut val = 0xf0;
+        LOW.pack_into(0xff, &mut val);
+        assert_eq!(val, 0xff);
+    }
+
+    #[test]
+    fn unpack_checked() {
+        const LOW: Pack32 = Pack32::least_significant(4);
+        const HIGH: Pack32 = LOW.remaining();
+        assert!(LOW.is_valid());
+        assert!(HIGH.is_valid());
+        assert_eq!(LOW.unpack_checked(0xabcd_ef12), Some(0x2));
+        assert_eq!(HIGH.unpack_checked(0xabcd_ef12), Some(0x0abc_def1));
+
+        // Split packing specs are valid as well.
+        let split = LOW.join(&Pack32::starting_at(9, 4));
+        assert!(split.is_valid());
+        assert_eq!(split.unpack_checked(0b1011_0000_0101), Some(0b1011_0101));
+
+        // A packing spec which starts beyond the last bit of a `u32`.
+        let beyond: Pack32 = Pack32 {
+            mask: 0,
+            shift: 32,
+            _dst_ty: PhantomData,
+        };
+        assert!(!beyond.is_valid());
+        assert_eq!(beyond.unpack_checked(u32::MAX), None);
+
+        // A packing spec whose shift does not match its mask.
+        let mismatched: Pack8 = Pack8 {
+            mask: 0b0000_1111,
+            shift: 6,
+            _dst_ty: PhantomData,
+        };
+        assert!(!mismatched.is_valid());
+        assert_eq!(mismatched.unpack_checked(u8::MAX), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "shift may not exceed maximum bits for u32")]
+    fn assert_valid_overflowing() {
+        let spec: Pack32 = Pack32 {
+            mask: 0,
+            shift: 32,
+            _dst_ty: PhantomData,
+        };
+        spec.assert_valid();
+    }
+
+    #[test]
+    fn assert_valid_typed() {
+        Pack32::least_significant(4)
+            .then::<u8>()
+            .assert_valid_typed();
+        Pack64::<bool>::first()
+            .next(3)
+            .then::<bool>()
+            .assert_valid_typed();
+    }
+
+    #[test]
+    #[should_panic(expected = "packing spec is 4 bits wide, but `FromBits::BITS` for u8 is 8")]
+    fn assert_valid_typed_mismatched() {
+        Pack32::least_significant(4)
+            .typed::<u8, ()>()
+            .assert_valid_typed();
+    }
+
+    #[test]
+    fn pack_ticks() {
+        use crate::Ticks;
+        use core::time::Duration;
+
+        const FLAG: Pack32<bool> = Pack32::first();
+        const TICKS: Pack32<Ticks<32_768, u16>> = FLAG.then::<Ticks<32_768, u16>>();
+        TICKS.assert_valid_typed();
+        assert_eq!(TICKS.offset(), 1);
+        assert_eq!(TICKS.width(), 16);
+
+        let val = TICKS.pack(Ticks::new(49_152), FLAG.pack(true, 0));
+        let ticks = TICKS.unpack(val);
+        assert_eq!(ticks.count(), 49_152);
+        assert_eq!(ticks.duration(), Duration::from_millis(1_500));
+        assert_eq!(TICKS.unpack_bits(val), 49_152);
+
+        // a single tick of a 32.768 kHz clock is not a whole number of
+        // nanoseconds.
+        assert_eq!(
+            Ticks::<32_768, u16>::new(1).duration(),
+            Duration::from_nanos(30_517)
+        );
+        assert_eq!(format!("{:?}", ticks), "1.5s");
+    }
+
+    #[test]
+    fn pack_char() {
+        const CHAR: Pack32<char> = Pack32::least_significant(3).then::<char>();
+        const FLAG: Pack32<bool> = CHAR.then::<bool>();
+        CHAR.assert_valid_typed();
+
+        let val = FLAG.pack(true, CHAR.pack('🦀', 0));
+        assert_eq!(CHAR.unpack(val), '🦀');
+        assert!(FLAG.unpack(val));
+
+        let val = CHAR.pack(char::MAX, 0);
+        assert_eq!(CHAR.unpack(val), char::MAX);
+        assert_eq!(CHAR.unpack_bits(val), 0x10_FFFF);
+    }
+
+    #[test]
+    fn char_from_bits() {
+        assert_eq!(<char as FromBits<u32>>::try_from_bits(0x41), Ok('A'));
+        assert_eq!(
+            <char as FromBits<u64>>::try_from_bits(0x10_FFFF),
+            Ok(char::MAX)
+        );
+        // surrogates are not valid `char`s
+        assert!(<char as FromBits<u32>>::try_from_bits(0xD800).is_err());
+        // neither are values beyond `char::MAX`
+        assert!(<char as FromBits<u32>>::try_from_bits(0x11_0000).is_err());
+        assert!(<char as FromBits<u64>>::try_from_bits(0x1_0000_0041).is_err());
+    }
+
+    #[test]
+    fn pack_ordering() {
+        use core::cmp::Ordering;
+
+        const ORD: Pack32<Ordering> = Pack32::least_significant(1).then::<Ordering>();
+        ORD.assert_valid_typed();
+
+        for (ord, bits) in [
+            (Ordering::Less, 0b00),
+            (Ordering::Equal, 0b01),
+            (Ordering::Greater, 0b10),
+        ] {
+            let val = ORD.pack(ord, 0);
+            assert_eq!(val, bits << 1);
+            assert_eq!(ORD.unpack(val), ord);
+            assert_eq!(
+                <Ordering as FromBits<u8>>::try_from_bits(bits as u8),
+                Ok(ord)
+            );
+        }
+
+        // `0b11` is not a valid `Ordering`.
+        let err = ORD.try_unpack(0b11 << 1).unwrap_err();
+        assert_eq!(err, crate::UnknownVariant::new("Ordering", 0b11));
+        assert!(<Ordering as FromBits<u64>>::try_from_bits(0b11).is_err());
+    }
+
+    #[test]
+    fn pack_nonzero() {
+        use core::num::{NonZeroI8, NonZeroU16};
+
+        const WORD: Pack32<NonZeroU16> = Pack32::least_significant(4).then::<NonZeroU16>();
+        const SIGNED: Pack32<NonZeroI8> = WORD.then::<NonZeroI8>();
+        WORD.assert_valid_typed();
+        SIGNED.assert_valid_typed();
+
+        let one = NonZeroU16::new(1).unwrap();
+        let neg = NonZeroI8::new(-2).unwrap();
+        let val = SIGNED.pack(neg, WORD.pack(one, 0));
+        assert_eq!(WORD.unpack(val), one);
+        assert_eq!(SIGNED.unpack(val), neg);
+        assert_eq!(val, (0xfe << 20) | (1 << 4));
+
+        // zero is not a valid `NonZero*` value
+        assert!(WORD.try_unpack(0).is_err());
+        assert!(<NonZeroU16 as FromBits<u32>>::try_from_bits(0).is_err());
+        assert!(<NonZeroI8 as FromBits<u8>>::try_from_bits(0).is_err());
+    }
+
+    #[test]
+    fn offset_and_width() {
+        const LOW: Pack32 = Pack32::least_significant(4);
+        const MID: Pack32 = LOW.next(8);
+        const HIGH: Pack32 = MID.remaining();
+
+        const _: () = assert!(MID.offset() == 4);
+        const _: () = assert!(MID.width() == 8);
+
+        assert_eq!(LOW.offset(), 0);
+        assert_eq!(LOW.width(), 4);
+        assert_eq!(MID.offset(), 4);
+        assert_eq!(MID.width(), 8);
+        assert_eq!(HIGH.offset(), 12);
+        assert_eq!(HIGH.width(), 20);
+        assert_eq!(HIGH.raw_mask(), ((1 << HIGH.width()) - 1) << HIGH.offset());
+
+        const _: () = assert!(MID.mask() == 0xff0);
+        assert_eq!(LOW.mask(), 0xf);
+        assert_eq!(MID.mask(), 0b1111_1111 << 4);
+        assert_eq!(HIGH.mask(), 0xffff_f000);
+        assert_eq!(HIGH.mask(), HIGH.max_value() << HIGH.offset());
+    }
+
     test_pack_unpack! {
+        fn pack_unpack_128<Pack128, u128>(128);
         fn pack_unpack_64<Pack64, u64>(64);
         fn pack_unpack_32<Pack32, u32>(32);
         fn pack_unpack_16<Pack16, u16>(16);
@@ -1250,6 +1823,7 @@ mod tests {
     }
 
     test_pack_methods! {
+        fn pack_methods_128<Pack128, u128>(128);
         fn pack_methods_64<Pack64, u64>(64);
         fn pack_methods_32<Pack32, u32>(32);
         fn pack_methods_16<Pack16, u16>(16);
@@ -1257,6 +1831,7 @@ mod tests {
     }
 
     test_from_range! {
+        fn pack_from_src_range_128<Pack128, u128>(128);
         fn pack_from_src_range_64<Pack64, u64>(64);
         fn pack_from_src_range_32<Pack32, u32>(32);
         fn pack_from_src_range_16<Pack16, u16>(16);
@@ -1264,6 +1839,7 @@ mod tests {
     }
 
     test_pair_least_sig_zeroed! {
+        fn pair_least_sig_zeroed_128<Pack128, u128>(128);
         fn pair_least_sig_zeroed_64<Pack64, u64>(64);
         fn pair_least_sig_zeroed_32<Pack32, u32>(32);
         fn pair_least_sig_zeroed_16<Pack16, u16>(16);
@@ -1271,6 +1847,7 @@ mod tests {
     }
 
     test_pair_least_sig_arbitrary! {
+        fn pair_least_sig_arbitrary_128<Pack128, u128>(128);
         fn pair_least_sig_arbitrary_64<Pack64, u64>(64);
         fn pair_least_sig_arbitrary_32<Pack32, u32>(32);
         fn pair_least_sig_arbitrary_16<Pack16, u16>(16);
@@ -1,6 +1,13 @@
-use core::fmt;
+use core::{
+    fmt,
+    iter::FromIterator,
+    ops::{Deref, DerefMut},
+};
 
 pub mod ctx;
+#[cfg(any(test, feature = "mock"))]
+#[doc(cfg(feature = "mock"))]
+pub mod mock;
 pub use self::ctx::Context;
 
 /// An interrupt controller for a platform.
@@ -27,20 +34,331 @@ pub trait Control {
     /// Returns `true` if interrupts are enabled.
     fn is_enabled(&self) -> bool;
 
+    /// Sets the interrupt priority mask to `level`.
+    ///
+    /// Once the priority mask is set, only interrupts with a priority *higher*
+    /// than `level` will be delivered. Setting the priority mask to
+    /// [`PriorityLevel::LOWEST`] unmasks interrupts of all priorities.
+    ///
+    /// # Default Implementation
+    ///
+    /// Does nothing. Platforms whose interrupt controllers do not support
+    /// priority-based masking need not implement this method.
+    fn set_priority_mask(&mut self, level: PriorityLevel) {
+        let _ = level;
+    }
+
+    /// Returns the current interrupt priority mask.
+    ///
+    /// See [`Control::set_priority_mask`] for details.
+    ///
+    /// # Default Implementation
+    ///
+    /// Returns [`PriorityLevel::LOWEST`].
+    fn priority_mask(&self) -> PriorityLevel {
+        PriorityLevel::LOWEST
+    }
+
+    /// Registers the interrupt handlers in `H` with this interrupt
+    /// controller.
+    ///
+    /// Implementations must call [`Handlers::init`] exactly once, before any
+    /// of `H`'s handlers are installed.
+    ///
+    /// If the platform cannot route any interrupt to some of `H`'s methods
+    /// (such as [`Handlers::keyboard_controller`] on a platform with no
+    /// keyboard controller), implementations should install the remaining
+    /// handlers, and then return [`RegistrationError::nonexistant`], with
+    /// the handlers that could not be registered listed using
+    /// [`RegistrationError::with_handlers`]. Callers which do not require
+    /// those handlers may choose to ignore such an error.
     fn register_handlers<H>(&mut self) -> Result<(), RegistrationError>
     where
         H: Handlers<Self::Registers>;
 
+    /// Signals the end of the interrupt on `vector` to the interrupt
+    /// controller.
+    ///
+    /// This should be called once the interrupt has been serviced (i.e.,
+    /// after the corresponding [`Handlers`] method returns), but before
+    /// returning from the interrupt. Until it is called, the controller may
+    /// not deliver further interrupts of the same or lower priority. Code which
+    /// re-enables interrupts inside an interrupt handler should consider
+    /// whether this must be called first.
+    ///
+    /// This must not be called for spurious interrupts (see
+    /// [`Handlers::spurious_interrupt`]).
+    ///
+    /// # Default Implementation
+    ///
+    /// Does nothing. Platforms whose interrupt controllers do not require an
+    /// end-of-interrupt signal need not implement this method.
+    fn end_of_interrupt(&mut self, vector: u8) {
+        let _ = vector;
+    }
+
+    /// Returns the vector of the interrupt currently being serviced, or
+    /// `None` if no interrupt is being serviced.
+    ///
+    /// This allows handler logic shared between several interrupt sources to
+    /// determine which source it was invoked for. The returned value is only
+    /// meaningful inside an interrupt handler; outside of one, this should
+    /// return `None`. If interrupts are nested, this returns the vector of the
+    /// innermost interrupt.
+    ///
+    /// # Default Implementation
+    ///
+    /// Returns `None`. Platforms whose interrupt controllers cannot report the
+    /// in-service vector need not implement this method.
+    fn current_vector(&self) -> Option<u8> {
+        None
+    }
+
+    /// Registers a single `handler` function for the interrupt `vector`.
+    ///
+    /// Unlike [`Control::register_handlers`], which installs handlers for
+    /// every interrupt the platform knows about at once, this installs a
+    /// handler for one vector at a time, such as for dynamically loaded
+    /// device drivers.
+    ///
+    /// Vectors which already have a handler installed, including those
+    /// installed by [`Control::register_handlers`], are considered occupied.
+    /// Registering a handler for an occupied vector fails with
+    /// [`RegistrationError::already_registered`], rather than replacing the
+    /// existing handler.
+    ///
+    /// # Default Implementation
+    ///
+    /// Returns [`RegistrationError::other`]`("unsupported")`. Platforms which
+    /// only support registering handlers statically need not implement this
+    /// method.
+    fn register_handler(
+        &mut self,
+        vector: u8,
+        handler: fn(&mut dyn ctx::Context<Registers = Self::Registers>),
+    ) -> Result<(), RegistrationError> {
+        let _ = (vector, handler);
+        Err(RegistrationError::other("unsupported"))
+    }
+
+    /// Sends an inter-processor interrupt (IPI) on `vector` to the CPU
+    /// identified by `target`.
+    ///
+    /// # Ordering
+    ///
+    /// This method makes no ordering guarantees. It may return before the
+    /// target CPU has received the interrupt, and IPIs sent to the same CPU
+    /// may be delivered in a different order than they were sent. IPIs on the
+    /// same vector may also be coalesced if the target has not yet serviced a
+    /// previous one. Writes made before sending an IPI are not guaranteed to
+    /// be visible to the target's interrupt handler, unless the caller
+    /// synchronizes them, such as with a release store that the handler
+    /// observes with an acquire load.
+    ///
+    /// # Default Implementation
+    ///
+    /// Returns [`RegistrationError::other`]`("unsupported")`. Platforms
+    /// without multiprocessor support need not implement this method.
+    fn send_ipi(&mut self, target: CpuId, vector: u8) -> Result<(), RegistrationError> {
+        let _ = (target, vector);
+        Err(RegistrationError::other("unsupported"))
+    }
+
+    /// Masks the interrupt `vector`, so that it will not be delivered until
+    /// it is unmasked by [`Control::unmask`].
+    ///
+    /// Returns `true` if `vector` was masked, or `false` if this controller
+    /// cannot mask it individually. Unlike [`Control::enter_critical`], this
+    /// allows other interrupts to be delivered while `vector` is masked.
+    /// Prefer [`Control::mask_scoped`], which unmasks the vector
+    /// automatically, and falls back to a critical section if the vector
+    /// cannot be masked.
+    ///
+    /// # Default Implementation
+    ///
+    /// Does nothing, and returns `false`. Platforms whose interrupt
+    /// controllers cannot mask individual vectors need not implement this
+    /// method.
+    fn mask(&mut self, vector: u8) -> bool {
+        let _ = vector;
+        false
+    }
+
+    /// Unmasks the interrupt `vector`, which was previously masked by
+    /// [`Control::mask`].
+    ///
+    /// This should only be called for vectors for which [`Control::mask`]
+    /// returned `true`.
+    ///
+    /// # Default Implementation
+    ///
+    /// Does nothing.
+    fn unmask(&mut self, vector: u8) {
+        let _ = vector;
+    }
+
+    /// Masks the interrupt `vector` (as with [`Control::mask`]), returning a
+    /// guard which unmasks it when dropped.
+    ///
+    /// This is useful for touching state shared with a single interrupt
+    /// handler without preventing other interrupts from being delivered.
+    ///
+    /// If this controller cannot mask `vector` individually, this falls back
+    /// to a full critical section, as with [`Control::enter_critical`]: all
+    /// interrupts are disabled, and when the guard is dropped, they are
+    /// re-enabled only if they were enabled when the guard was created.
+    fn mask_scoped(&mut self, vector: u8) -> MaskGuard<'_, Self> {
+        let masked = self.mask(vector);
+        let was_enabled = !masked && self.is_enabled();
+        if was_enabled {
+            unsafe {
+                self.disable();
+            }
+        }
+        if !masked {
+            let depth = self.critical_depth();
+            self.set_critical_depth(depth + 1);
+        }
+        MaskGuard {
+            ctrl: self,
+            vector,
+            vector_masked: masked,
+            was_enabled,
+        }
+    }
+
     /// Enter a critical section, returning a guard.
+    ///
+    /// Interrupts are disabled until the returned guard is dropped. Critical
+    /// sections may be nested: if interrupts were already disabled when the
+    /// critical section was entered, they are not re-enabled when the guard
+    /// is dropped, so only the outermost critical section re-enables
+    /// interrupts. A nested critical section may be entered through an
+    /// existing guard, which dereferences to the interrupt controller.
     fn enter_critical(&mut self) -> CriticalGuard<'_, Self> {
-        unsafe {
-            self.disable();
+        let was_enabled = self.is_enabled();
+        if was_enabled {
+            unsafe {
+                self.disable();
+            }
+        }
+        let depth = self.critical_depth();
+        self.set_critical_depth(depth + 1);
+        CriticalGuard {
+            ctrl: self,
+            was_enabled,
+            prev_mask: None,
+        }
+    }
+
+    /// Enter a critical section at the given priority `level`, returning a
+    /// guard.
+    ///
+    /// Rather than disabling all interrupts, as [`Control::enter_critical`]
+    /// does, this raises the interrupt priority mask (see
+    /// [`Control::set_priority_mask`]) to `level`, so that interrupts with a
+    /// priority higher than `level` may still be serviced inside the critical
+    /// section. When the returned guard is dropped, the priority mask is
+    /// restored to its previous value.
+    ///
+    /// If the current priority mask is already at or above `level`, it is
+    /// left unchanged, so nested critical sections never lower the mask.
+    ///
+    /// Note that on platforms which do not support priority-based masking,
+    /// [`Control::set_priority_mask`] does nothing, so this does *not* mask
+    /// any interrupts. Code which requires that no interrupts occur should use
+    /// [`Control::enter_critical`] instead.
+    ///
+    /// Because interrupts are not disabled, a critical section entered at a
+    /// priority level is not counted by [`Control::critical_depth`].
+    fn enter_critical_at(&mut self, level: PriorityLevel) -> CriticalGuard<'_, Self> {
+        let prev_mask = self.priority_mask();
+        if level > prev_mask {
+            self.set_priority_mask(level);
         }
-        CriticalGuard { ctrl: self }
+        CriticalGuard {
+            ctrl: self,
+            was_enabled: false,
+            prev_mask: Some(prev_mask),
+        }
+    }
+
+    /// Returns the number of [`CriticalGuard`]s returned by
+    /// [`Control::enter_critical`] for this interrupt controller that are
+    /// currently held.
+    ///
+    /// This is intended for debugging, such as asserting that a function is
+    /// called outside of any critical section (`critical_depth() == 0`). Only
+    /// critical sections which disable interrupts are counted; those entered
+    /// at a priority level with [`Control::enter_critical_at`] are not, since
+    /// higher-priority interrupts may still be delivered inside them.
+    ///
+    /// # Default Implementation
+    ///
+    /// Returns 0. Implementations which track the critical section depth
+    /// must also implement [`Control::set_critical_depth`].
+    fn critical_depth(&self) -> usize {
+        0
+    }
+
+    /// Sets the critical section depth returned by
+    /// [`Control::critical_depth`].
+    ///
+    /// This is called by [`Control::enter_critical`] and by
+    /// [`CriticalGuard`]'s `Drop` implementation, and should generally not be
+    /// called otherwise.
+    ///
+    /// # Default Implementation
+    ///
+    /// Does nothing.
+    #[doc(hidden)]
+    fn set_critical_depth(&mut self, depth: usize) {
+        let _ = depth;
+    }
+
+    /// Runs the provided closure inside a critical section, returning its
+    /// result.
+    ///
+    /// Interrupts are disabled while `f` runs, and are restored to their
+    /// previous state (as with [`Control::enter_critical`]) when `f` returns.
+    ///
+    /// # Panics
+    ///
+    /// If `f` panics and the panic unwinds (i.e. when building with
+    /// `panic = "unwind"`), interrupts are still restored to their previous
+    /// state as the panic unwinds past this function. With
+    /// `panic = "abort"`, interrupts are not restored.
+    fn without_interrupts<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        let _guard = self.enter_critical();
+        f()
     }
 }
 
 pub trait Handlers<R: fmt::Debug + fmt::Display> {
+    /// Called once when this set of handlers is registered by
+    /// [`Control::register_handlers`].
+    ///
+    /// This may be used to perform one-time initialization which must happen
+    /// before any interrupts are handled, such as allocating per-CPU state or
+    /// resetting a timer counter.
+    ///
+    /// # Ordering
+    ///
+    /// This is called before any of the handlers are installed, so it never
+    /// runs concurrently with, or after, an interrupt handled by this set of
+    /// handlers. Registering handlers does not enable interrupts, so if the
+    /// platform enables interrupts (with [`Control::enable`]) only after
+    /// registering its handlers, this is also called before interrupts are
+    /// enabled.
+    ///
+    /// By default, this does nothing.
+    fn init() {
+        // nop
+    }
+
     fn page_fault<C>(cx: C)
     where
         C: ctx::Context<Registers = R> + ctx::PageFault;
@@ -53,9 +371,81 @@ pub trait Handlers<R: fmt::Debug + fmt::Display> {
     where
         C: ctx::Context<Registers = R>;
 
-    fn timer_tick();
+    /// Called on each tick of the platform's timer.
+    ///
+    /// Platforms which know the period of their timer should call
+    /// [`Handlers::timer_tick_elapsed`] instead, which calls this method by
+    /// default.
+    ///
+    /// The returned [`TickAction`] tells the platform whether the kernel
+    /// would like to switch to another task before the interrupted code
+    /// resumes. See [`TickAction`] for details on how the platform acts on
+    /// it.
+    fn timer_tick() -> TickAction;
+
+    /// Called on each tick of the platform's timer, with the [`Context`] of
+    /// the code that the timer interrupt preempted.
+    ///
+    /// This allows a preemptive scheduler to inspect (and save) the registers
+    /// of the interrupted task. Whether mutating the context through
+    /// [`Context::registers_mut`] affects the state that is restored when
+    /// the interrupt returns is platform-specific.
+    ///
+    /// By default, this ignores the context and calls
+    /// [`Handlers::timer_tick`].
+    fn timer_tick_cx<C>(_cx: C) -> TickAction
+    where
+        C: ctx::Context<Registers = R>,
+    {
+        Self::timer_tick()
+    }
+
+    /// Called on each tick of the platform's timer, with the duration that
+    /// has elapsed since the previous tick.
+    ///
+    /// Platforms with a known timer period should call this method rather
+    /// than [`Handlers::timer_tick`], so that the kernel can maintain a
+    /// monotonic clock from the reported durations. Platforms which cannot
+    /// determine the elapsed time call [`Handlers::timer_tick`] directly.
+    ///
+    /// By default, this ignores the elapsed duration and calls
+    /// [`Handlers::timer_tick`], so kernels which only count ticks need only
+    /// implement that method.
+    fn timer_tick_elapsed(_dur: core::time::Duration) -> TickAction {
+        Self::timer_tick()
+    }
+
+    /// Called when the keyboard controller signals that a key was pressed or
+    /// released, with the `scancode` read from the controller.
+    ///
+    /// The platform is responsible for reading the scancode from the keyboard
+    /// controller, so that the controller will continue to send interrupts on
+    /// later keystrokes.
+    fn keyboard_controller(scancode: u8);
 
-    fn keyboard_controller();
+    /// Called when a device interrupt occurs on the given interrupt `vector`.
+    ///
+    /// Platform code may route interrupts from vectors that do not have a
+    /// dedicated method on this trait (such as [`Handlers::timer_tick`] or
+    /// [`Handlers::keyboard_controller`]) to this method, so that the kernel
+    /// can dispatch them to the appropriate device driver.
+    ///
+    /// By default, this does nothing.
+    fn device_interrupt(_vector: u8) {
+        // nop
+    }
+
+    /// Called when the interrupt controller delivers a spurious interrupt.
+    ///
+    /// Spurious interrupts do not correspond to a real interrupt request, so
+    /// neither the platform nor the handler may send an end-of-interrupt
+    /// (EOI) signal to the interrupt controller in response to one. Doing so
+    /// may acknowledge a different, in-service interrupt.
+    ///
+    /// By default, this does nothing.
+    fn spurious_interrupt() {
+        // nop
+    }
 
     fn test_interrupt<C>(_cx: C)
     where
@@ -65,31 +455,364 @@ pub trait Handlers<R: fmt::Debug + fmt::Display> {
     }
 }
 
+/// The action requested by a timer tick handler, returned by
+/// [`Handlers::timer_tick`].
+///
+/// # Platform Responsibilities
+///
+/// If a timer tick handler returns [`TickAction::Reschedule`], the platform
+/// should switch to another task before returning from the timer interrupt,
+/// after signaling the end of the interrupt (see
+/// [`Control::end_of_interrupt`]). How the switch is performed is
+/// platform-specific. Platforms which do not yet support switching tasks from
+/// an interrupt handler may treat `Reschedule` as [`TickAction::Continue`],
+/// and should document that they do so.
+///
+/// If the handler returns [`TickAction::Continue`], the platform returns from
+/// the interrupt as usual.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[must_use]
+pub enum TickAction {
+    /// Resume the interrupted code. This is the [`Default`].
+    #[default]
+    Continue,
+    /// Switch to another task before resuming the interrupted code, such as
+    /// when the interrupted task's time slice has expired.
+    Reschedule,
+}
+
+/// An interrupt priority level.
+///
+/// Higher values represent higher priorities. Priority levels are used by
+/// [`Control::set_priority_mask`] to mask interrupts below a threshold
+/// priority. How priority levels map onto the hardware's priority classes is
+/// platform-specific.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(transparent)]
+pub struct PriorityLevel(u8);
+
+/// Identifies a CPU, as the target of an inter-processor interrupt sent by
+/// [`Control::send_ipi`].
+///
+/// How CPU IDs are assigned is platform-specific (e.g., on x86_64, this is
+/// the CPU's local APIC ID).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(transparent)]
+pub struct CpuId(u32);
+
 /// Errors that may occur while registering an interrupt handler.
 #[derive(Clone, Eq, PartialEq)]
 pub struct RegistrationError {
     kind: RegistrationErrorKind,
+    handlers: HandlerSet,
 }
 
+/// A guard representing a critical section, returned by
+/// [`Control::enter_critical`] and [`Control::enter_critical_at`].
+///
+/// When this guard is dropped, interrupts are re-enabled if they were enabled
+/// when the critical section was entered, or, if the critical section was
+/// entered at a priority level, the previous priority mask is restored.
 #[derive(Debug)]
 pub struct CriticalGuard<'a, C: Control + ?Sized> {
     ctrl: &'a mut C,
+    was_enabled: bool,
+    prev_mask: Option<PriorityLevel>,
+}
+
+/// A guard representing a masked interrupt vector, returned by
+/// [`Control::mask_scoped`].
+///
+/// When this guard is dropped, the vector is unmasked by
+/// [`Control::unmask`]. If the controller could not mask the vector, and a
+/// critical section was entered instead, interrupts are re-enabled if they
+/// were enabled when the guard was created.
+#[derive(Debug)]
+pub struct MaskGuard<'a, C: Control + ?Sized> {
+    ctrl: &'a mut C,
+    vector: u8,
+    vector_masked: bool,
+    was_enabled: bool,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
-enum RegistrationErrorKind {
+/// The kind of a [`RegistrationError`], returned by
+/// [`RegistrationError::kind`].
+#[derive(Copy, Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum RegistrationErrorKind {
+    /// The interrupt vector being registered does not exist.
     Nonexistant,
+    /// The interrupt vector being registered has already been registered.
     AlreadyRegistered,
+    /// A platform-specific error, with a message describing it.
     Other(&'static str),
 }
 
+/// Identifies a method of the [`Handlers`] trait.
+///
+/// This is used by [`HandlerSet`] to report which handlers could not be
+/// registered by [`Control::register_handlers`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[repr(u8)]
+pub enum HandlerKind {
+    /// [`Handlers::page_fault`].
+    PageFault,
+    /// [`Handlers::code_fault`].
+    CodeFault,
+    /// [`Handlers::double_fault`].
+    DoubleFault,
+    /// [`Handlers::timer_tick`] (and [`Handlers::timer_tick_cx`] and
+    /// [`Handlers::timer_tick_elapsed`]).
+    TimerTick,
+    /// [`Handlers::keyboard_controller`].
+    KeyboardController,
+    /// [`Handlers::device_interrupt`].
+    DeviceInterrupt,
+    /// [`Handlers::spurious_interrupt`].
+    SpuriousInterrupt,
+}
+
+/// A set of [`HandlerKind`]s.
+///
+/// This is returned by [`RegistrationError::handlers`], to report which
+/// [`Handlers`] methods a platform could not route any interrupt to.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct HandlerSet(u8);
+
 // === impl CriticalGuard ===
 
 impl<'a, C: Control + ?Sized> Drop for CriticalGuard<'a, C> {
     fn drop(&mut self) {
-        unsafe {
-            self.ctrl.enable();
+        if let Some(prev_mask) = self.prev_mask {
+            self.ctrl.set_priority_mask(prev_mask);
+            return;
+        }
+        let depth = self.ctrl.critical_depth();
+        self.ctrl.set_critical_depth(depth.saturating_sub(1));
+        if self.was_enabled {
+            unsafe {
+                self.ctrl.enable();
+            }
+        }
+    }
+}
+
+impl<'a, C: Control + ?Sized> Deref for CriticalGuard<'a, C> {
+    type Target = C;
+
+    fn deref(&self) -> &Self::Target {
+        self.ctrl
+    }
+}
+
+impl<'a, C: Control + ?Sized> DerefMut for CriticalGuard<'a, C> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.ctrl
+    }
+}
+
+// === impl MaskGuard ===
+
+impl<'a, C: Control + ?Sized> MaskGuard<'a, C> {
+    /// Returns the interrupt vector masked by this guard.
+    pub fn vector(&self) -> u8 {
+        self.vector
+    }
+}
+
+impl<'a, C: Control + ?Sized> Drop for MaskGuard<'a, C> {
+    fn drop(&mut self) {
+        if self.vector_masked {
+            self.ctrl.unmask(self.vector);
+            return;
+        }
+        let depth = self.ctrl.critical_depth();
+        self.ctrl.set_critical_depth(depth.saturating_sub(1));
+        if self.was_enabled {
+            unsafe {
+                self.ctrl.enable();
+            }
+        }
+    }
+}
+
+impl<'a, C: Control + ?Sized> Deref for MaskGuard<'a, C> {
+    type Target = C;
+
+    fn deref(&self) -> &Self::Target {
+        self.ctrl
+    }
+}
+
+impl<'a, C: Control + ?Sized> DerefMut for MaskGuard<'a, C> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.ctrl
+    }
+}
+
+// === impl TickAction ===
+
+impl TickAction {
+    /// Returns `true` if this is [`TickAction::Reschedule`].
+    pub fn is_reschedule(self) -> bool {
+        matches!(self, Self::Reschedule)
+    }
+}
+
+// === impl PriorityLevel ===
+
+impl PriorityLevel {
+    /// The lowest priority level.
+    ///
+    /// Setting the priority mask to this level masks no interrupts.
+    pub const LOWEST: Self = Self(0);
+
+    /// The highest priority level.
+    pub const HIGHEST: Self = Self(u8::MAX);
+
+    /// Returns a new priority level with the provided numeric value.
+    pub const fn new(level: u8) -> Self {
+        Self(level)
+    }
+
+    /// Returns the numeric value of this priority level.
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl Default for PriorityLevel {
+    fn default() -> Self {
+        Self::LOWEST
+    }
+}
+
+impl fmt::Display for PriorityLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+// === impl CpuId ===
+
+impl CpuId {
+    /// Returns a new CPU ID with the provided numeric value.
+    pub const fn new(id: u32) -> Self {
+        Self(id)
+    }
+
+    /// Returns the numeric value of this CPU ID.
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for CpuId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+// === impl HandlerKind ===
+
+impl HandlerKind {
+    /// Every `HandlerKind`, in declaration order.
+    pub const ALL: [Self; 7] = [
+        Self::PageFault,
+        Self::CodeFault,
+        Self::DoubleFault,
+        Self::TimerTick,
+        Self::KeyboardController,
+        Self::DeviceInterrupt,
+        Self::SpuriousInterrupt,
+    ];
+
+    /// Returns the name of the [`Handlers`] method this identifies.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::PageFault => "page_fault",
+            Self::CodeFault => "code_fault",
+            Self::DoubleFault => "double_fault",
+            Self::TimerTick => "timer_tick",
+            Self::KeyboardController => "keyboard_controller",
+            Self::DeviceInterrupt => "device_interrupt",
+            Self::SpuriousInterrupt => "spurious_interrupt",
+        }
+    }
+}
+
+impl fmt::Display for HandlerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// === impl HandlerSet ===
+
+impl HandlerSet {
+    /// Returns a new, empty set.
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    /// Returns a new set containing `handler` in addition to the handlers in
+    /// `self`.
+    pub const fn with(self, handler: HandlerKind) -> Self {
+        Self(self.0 | 1 << handler as u8)
+    }
+
+    /// Adds `handler` to this set.
+    pub fn insert(&mut self, handler: HandlerKind) {
+        *self = self.with(handler);
+    }
+
+    /// Returns `true` if this set contains `handler`.
+    pub const fn contains(self, handler: HandlerKind) -> bool {
+        self.0 & 1 << handler as u8 != 0
+    }
+
+    /// Returns `true` if this set contains no handlers.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the number of handlers in this set.
+    pub const fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Returns an iterator over the handlers in this set, in the order they
+    /// are declared in [`HandlerKind`].
+    pub fn iter(self) -> impl Iterator<Item = HandlerKind> {
+        HandlerKind::ALL
+            .iter()
+            .copied()
+            .filter(move |&handler| self.contains(handler))
+    }
+}
+
+impl fmt::Debug for HandlerSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl fmt::Display for HandlerSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut handlers = self.iter();
+        if let Some(first) = handlers.next() {
+            fmt::Display::fmt(&first, f)?;
+            for handler in handlers {
+                write!(f, ", {}", handler)?;
+            }
         }
+        Ok(())
+    }
+}
+
+impl FromIterator<HandlerKind> for HandlerSet {
+    fn from_iter<I: IntoIterator<Item = HandlerKind>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::new(), Self::with)
     }
 }
 
@@ -97,31 +820,72 @@ impl<'a, C: Control + ?Sized> Drop for CriticalGuard<'a, C> {
 impl RegistrationError {
     /// Returns a new error indicating that the registered interrupt vector does
     /// not exist.
+    ///
+    /// When returned by [`Control::register_handlers`], the handlers which
+    /// could not be registered may be listed using
+    /// [`RegistrationError::with_handlers`].
     pub fn nonexistant() -> Self {
-        Self {
-            kind: RegistrationErrorKind::Nonexistant,
-        }
+        Self::new(RegistrationErrorKind::Nonexistant)
     }
 
     /// Returns a new error indicating that the registered interrupt vector has
     /// already been registered and cannot be registered again.
     pub fn already_registered() -> Self {
-        Self {
-            kind: RegistrationErrorKind::AlreadyRegistered,
-        }
+        Self::new(RegistrationErrorKind::AlreadyRegistered)
     }
 
     /// Returns a new platform-specific error with the provided message.
     pub fn other(message: &'static str) -> Self {
+        Self::new(RegistrationErrorKind::Other(message))
+    }
+
+    fn new(kind: RegistrationErrorKind) -> Self {
         Self {
-            kind: RegistrationErrorKind::Other(message),
+            kind,
+            handlers: HandlerSet::new(),
+        }
+    }
+
+    /// Returns this error, listing the [`Handlers`] methods in `handlers` as
+    /// the handlers which could not be registered.
+    pub fn with_handlers(self, handlers: HandlerSet) -> Self {
+        Self { handlers, ..self }
+    }
+
+    /// Returns the set of [`Handlers`] methods which could not be
+    /// registered.
+    ///
+    /// This is empty unless the error was returned by
+    /// [`Control::register_handlers`] and constructed using
+    /// [`RegistrationError::with_handlers`].
+    pub fn handlers(&self) -> HandlerSet {
+        self.handlers
+    }
+
+    /// Returns the [kind] of this error.
+    ///
+    /// [kind]: RegistrationErrorKind
+    pub fn kind(&self) -> RegistrationErrorKind {
+        self.kind
+    }
+
+    /// Returns the platform-specific message for this error, if it was
+    /// constructed with [`RegistrationError::other`].
+    pub fn message(&self) -> Option<&'static str> {
+        match self.kind {
+            RegistrationErrorKind::Other(message) => Some(message),
+            _ => None,
         }
     }
 
+    /// Returns `true` if this error indicates that the registered interrupt
+    /// vector does not exist.
     pub fn is_nonexistant(&self) -> bool {
         matches!(self.kind, RegistrationErrorKind::Nonexistant)
     }
 
+    /// Returns `true` if this error indicates that the registered interrupt
+    /// vector has already been registered.
     pub fn is_already_registered(&self) -> bool {
         matches!(self.kind, RegistrationErrorKind::AlreadyRegistered)
     }
@@ -131,6 +895,627 @@ impl fmt::Debug for RegistrationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RegistrationError")
             .field("kind", &self.kind)
+            .field("handlers", &self.handlers)
             .finish()
     }
 }
+
+impl fmt::Display for RegistrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            RegistrationErrorKind::Nonexistant => f.write_str("interrupt vector does not exist")?,
+            RegistrationErrorKind::AlreadyRegistered => {
+                f.write_str("interrupt vector has already been registered")?
+            }
+            RegistrationErrorKind::Other(message) => f.write_str(message)?,
+        }
+        if !self.handlers.is_empty() {
+            write!(f, " (handlers: {})", self.handlers)?;
+        }
+        Ok(())
+    }
+}
+
+// TODO: `core::error::Error` is not available on our pinned nightly;
+// once the toolchain is updated, this can implement it unconditionally.
+#[cfg(not(target_os = "none"))]
+impl std::error::Error for RegistrationError {}
+
+#[cfg(test)]
+mod tests {
+    use super::mock::{MockController, MockRegisters};
+    use super::*;
+    use core::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+
+    /// Mock interrupt handlers which record the last scancode passed to
+    /// `keyboard_controller`.
+    struct MockHandlers;
+
+    static LAST_SCANCODE: AtomicU8 = AtomicU8::new(0);
+    static TICKS: AtomicUsize = AtomicUsize::new(0);
+    static INITS: AtomicUsize = AtomicUsize::new(0);
+
+    impl Handlers<MockRegisters> for MockHandlers {
+        fn init() {
+            INITS.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn page_fault<C>(_cx: C)
+        where
+            C: ctx::Context<Registers = MockRegisters> + ctx::PageFault,
+        {
+            unreachable!()
+        }
+
+        fn code_fault<C>(_cx: C)
+        where
+            C: ctx::Context<Registers = MockRegisters> + ctx::CodeFault,
+        {
+            unreachable!()
+        }
+
+        fn double_fault<C>(_cx: C)
+        where
+            C: ctx::Context<Registers = MockRegisters>,
+        {
+            unreachable!()
+        }
+
+        fn timer_tick() -> TickAction {
+            TICKS.fetch_add(1, Ordering::Relaxed);
+            TickAction::Continue
+        }
+
+        fn keyboard_controller(scancode: u8) {
+            LAST_SCANCODE.store(scancode, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn keyboard_controller_scancode() {
+        MockHandlers::keyboard_controller(0x1c);
+        assert_eq!(LAST_SCANCODE.load(Ordering::Relaxed), 0x1c);
+    }
+
+    #[test]
+    fn timer_tick_elapsed_default() {
+        let ticks = TICKS.load(Ordering::Relaxed);
+        let action = MockHandlers::timer_tick_elapsed(core::time::Duration::from_millis(10));
+        assert_eq!(action, TickAction::Continue);
+        // other tests may tick the timer concurrently.
+        assert!(TICKS.load(Ordering::Relaxed) > ticks);
+    }
+
+    #[test]
+    fn tick_action() {
+        assert_eq!(TickAction::default(), TickAction::Continue);
+        assert!(!TickAction::Continue.is_reschedule());
+        assert!(TickAction::Reschedule.is_reschedule());
+    }
+
+    #[test]
+    fn critical_guard() {
+        let mut ctrl = MockController::enabled();
+        {
+            let guard = ctrl.enter_critical();
+            assert!(!guard.is_enabled());
+        }
+        assert!(ctrl.is_enabled());
+    }
+
+    #[test]
+    fn critical_guard_nested() {
+        let mut ctrl = MockController::enabled();
+        {
+            let mut outer = ctrl.enter_critical();
+            assert!(!outer.is_enabled());
+            {
+                let inner = outer.enter_critical();
+                assert!(!inner.is_enabled());
+            }
+            // Dropping the inner guard must not re-enable interrupts.
+            assert!(!outer.is_enabled());
+        }
+        assert!(ctrl.is_enabled());
+    }
+
+    #[test]
+    fn critical_guard_already_disabled() {
+        let mut ctrl = MockController::default();
+        {
+            let guard = ctrl.enter_critical();
+            assert!(!guard.is_enabled());
+        }
+        // Interrupts were disabled when the critical section was entered, so
+        // they should remain disabled.
+        assert!(!ctrl.is_enabled());
+    }
+
+    #[test]
+    fn critical_guard_nested_counts() {
+        let mut ctrl = MockController::enabled();
+        {
+            let mut outer = ctrl.enter_critical();
+            {
+                let mut inner = outer.enter_critical();
+                let _innermost = inner.enter_critical();
+            }
+            assert_eq!(outer.critical_depth(), 1);
+        }
+        // Only the outermost critical section disables and re-enables
+        // interrupts.
+        assert_eq!(ctrl.disable_count(), 1);
+        assert_eq!(ctrl.enable_count(), 1);
+        assert_eq!(ctrl.max_critical_depth(), 3);
+        assert_eq!(ctrl.critical_depth(), 0);
+    }
+
+    #[test]
+    fn register_handlers_recorded() {
+        let mut ctrl = MockController::new();
+        assert_eq!(ctrl.register_handlers_count(), 0);
+        assert_eq!(ctrl.last_handlers(), None);
+
+        ctrl.register_handlers::<MockHandlers>().unwrap();
+        assert_eq!(ctrl.register_handlers_count(), 1);
+        assert!(ctrl.last_handlers().unwrap().ends_with("MockHandlers"));
+    }
+
+    #[test]
+    fn register_handlers_unsupported() {
+        let unsupported = HandlerSet::new().with(HandlerKind::KeyboardController);
+        let mut ctrl = MockController::new().with_unsupported_handlers(unsupported);
+
+        let err = ctrl.register_handlers::<MockHandlers>().unwrap_err();
+        assert!(err.is_nonexistant());
+        assert_eq!(err.handlers(), unsupported);
+        assert!(err.handlers().contains(HandlerKind::KeyboardController));
+        assert!(!err.handlers().contains(HandlerKind::TimerTick));
+        assert_eq!(
+            err.to_string(),
+            "interrupt vector does not exist (handlers: keyboard_controller)"
+        );
+        // the supported handlers are still registered.
+        assert_eq!(ctrl.register_handlers_count(), 1);
+    }
+
+    #[test]
+    fn handler_set() {
+        let mut set = HandlerSet::new();
+        assert!(set.is_empty());
+        set.insert(HandlerKind::TimerTick);
+        set.insert(HandlerKind::PageFault);
+        set.insert(HandlerKind::TimerTick);
+        assert_eq!(set.len(), 2);
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            [HandlerKind::PageFault, HandlerKind::TimerTick]
+        );
+        assert_eq!(set.to_string(), "page_fault, timer_tick");
+        assert_eq!(
+            set,
+            [HandlerKind::TimerTick, HandlerKind::PageFault]
+                .iter()
+                .copied()
+                .collect::<HandlerSet>()
+        );
+        assert_eq!(
+            HandlerKind::ALL
+                .iter()
+                .copied()
+                .collect::<HandlerSet>()
+                .len(),
+            HandlerKind::ALL.len()
+        );
+    }
+
+    #[test]
+    fn register_handlers_calls_init() {
+        let inits = INITS.load(Ordering::Relaxed);
+        let mut ctrl = MockController::new();
+        ctrl.register_handlers::<MockHandlers>().unwrap();
+        // other tests may register handlers concurrently.
+        assert!(INITS.load(Ordering::Relaxed) > inits);
+    }
+
+    #[test]
+    fn without_interrupts() {
+        let mut ctrl = MockController::enabled();
+        let ret = ctrl.without_interrupts(|| 42);
+        assert_eq!(ret, 42);
+        assert!(ctrl.is_enabled());
+
+        let mut ctrl = MockController::default();
+        ctrl.without_interrupts(|| {});
+        assert!(!ctrl.is_enabled());
+    }
+
+    #[test]
+    fn without_interrupts_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut ctrl = MockController::enabled();
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            ctrl.without_interrupts(|| panic!("oh no"));
+        }));
+        assert!(result.is_err());
+        assert!(ctrl.is_enabled());
+    }
+
+    /// A mock interrupt controller which supports priority masking.
+    #[derive(Debug, Default)]
+    struct MockPriorityControl {
+        mask: PriorityLevel,
+    }
+
+    impl Control for MockPriorityControl {
+        type Registers = MockRegisters;
+
+        unsafe fn disable(&mut self) {
+            unreachable!("priority critical sections must not disable interrupts")
+        }
+
+        unsafe fn enable(&mut self) {
+            unreachable!("priority critical sections must not enable interrupts")
+        }
+
+        fn is_enabled(&self) -> bool {
+            true
+        }
+
+        fn set_priority_mask(&mut self, level: PriorityLevel) {
+            self.mask = level;
+        }
+
+        fn priority_mask(&self) -> PriorityLevel {
+            self.mask
+        }
+
+        fn register_handlers<H>(&mut self) -> Result<(), RegistrationError>
+        where
+            H: Handlers<Self::Registers>,
+        {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn critical_guard_at() {
+        let mut ctrl = MockPriorityControl::default();
+        {
+            let mut guard = ctrl.enter_critical_at(PriorityLevel::new(4));
+            assert_eq!(guard.priority_mask(), PriorityLevel::new(4));
+            {
+                let guard = guard.enter_critical_at(PriorityLevel::new(8));
+                assert_eq!(guard.priority_mask(), PriorityLevel::new(8));
+            }
+            assert_eq!(guard.priority_mask(), PriorityLevel::new(4));
+            {
+                // entering at a lower level does not lower the mask.
+                let guard = guard.enter_critical_at(PriorityLevel::new(2));
+                assert_eq!(guard.priority_mask(), PriorityLevel::new(4));
+            }
+            assert_eq!(guard.priority_mask(), PriorityLevel::new(4));
+        }
+        assert_eq!(ctrl.priority_mask(), PriorityLevel::LOWEST);
+    }
+
+    #[test]
+    fn critical_guard_at_depth() {
+        let mut ctrl = MockController::enabled();
+        {
+            let mut guard = ctrl.enter_critical_at(PriorityLevel::new(4));
+            // Priority critical sections do not disable interrupts, so they
+            // are not counted.
+            assert_eq!(guard.critical_depth(), 0);
+            assert!(guard.is_enabled());
+            {
+                let guard = guard.enter_critical();
+                assert_eq!(guard.critical_depth(), 1);
+                assert!(!guard.is_enabled());
+            }
+            assert_eq!(guard.critical_depth(), 0);
+            assert!(guard.is_enabled());
+        }
+        assert_eq!(ctrl.critical_depth(), 0);
+        assert_eq!(ctrl.max_critical_depth(), 1);
+        assert!(ctrl.is_enabled());
+    }
+
+    #[test]
+    fn priority_mask_default() {
+        let mut ctrl = MockController::default();
+        assert_eq!(ctrl.priority_mask(), PriorityLevel::LOWEST);
+        ctrl.set_priority_mask(PriorityLevel::new(4));
+        assert_eq!(ctrl.priority_mask(), PriorityLevel::LOWEST);
+    }
+
+    #[test]
+    fn current_vector_default() {
+        let ctrl = MockController::default();
+        assert_eq!(ctrl.current_vector(), None);
+    }
+
+    #[test]
+    fn priority_level_ordering() {
+        assert!(PriorityLevel::LOWEST < PriorityLevel::new(1));
+        assert!(PriorityLevel::new(1) < PriorityLevel::HIGHEST);
+        assert_eq!(PriorityLevel::default(), PriorityLevel::LOWEST);
+        assert_eq!(PriorityLevel::new(7).get(), 7);
+    }
+
+    #[test]
+    fn registration_error_nonexistant() {
+        let err = RegistrationError::nonexistant();
+        assert_eq!(err.kind(), RegistrationErrorKind::Nonexistant);
+        assert!(err.is_nonexistant());
+        assert!(!err.is_already_registered());
+        assert_eq!(err.message(), None);
+    }
+
+    #[test]
+    fn registration_error_already_registered() {
+        let err = RegistrationError::already_registered();
+        assert_eq!(err.kind(), RegistrationErrorKind::AlreadyRegistered);
+        assert!(!err.is_nonexistant());
+        assert!(err.is_already_registered());
+        assert_eq!(err.message(), None);
+    }
+
+    #[test]
+    fn registration_error_other() {
+        let err = RegistrationError::other("the vector is cursed");
+        assert_eq!(
+            err.kind(),
+            RegistrationErrorKind::Other("the vector is cursed")
+        );
+        assert!(!err.is_nonexistant());
+        assert!(!err.is_already_registered());
+        assert_eq!(err.message(), Some("the vector is cursed"));
+    }
+
+    #[test]
+    fn registration_error_display() {
+        assert_eq!(
+            RegistrationError::nonexistant().to_string(),
+            "interrupt vector does not exist"
+        );
+        assert_eq!(
+            RegistrationError::already_registered().to_string(),
+            "interrupt vector has already been registered"
+        );
+        assert_eq!(
+            RegistrationError::other("the vector is cursed").to_string(),
+            "the vector is cursed"
+        );
+    }
+
+    #[test]
+    fn registration_error_is_error() {
+        fn boxed() -> Result<(), Box<dyn std::error::Error>> {
+            Err(RegistrationError::nonexistant())?
+        }
+        assert_eq!(
+            boxed().unwrap_err().to_string(),
+            "interrupt vector does not exist"
+        );
+    }
+
+    #[test]
+    fn register_handler_default() {
+        fn handler(_cx: &mut dyn ctx::Context<Registers = MockRegisters>) {}
+
+        let mut ctrl = MockController::default();
+        let err = ctrl.register_handler(0x30, handler).unwrap_err();
+        assert_eq!(err.message(), Some("unsupported"));
+    }
+
+    #[test]
+    fn send_ipi_default() {
+        let mut ctrl = MockController::default();
+        let err = ctrl.send_ipi(CpuId::new(1), 0x40).unwrap_err();
+        assert_eq!(err.message(), Some("unsupported"));
+        assert_eq!(CpuId::new(1).get(), 1);
+    }
+
+    #[test]
+    fn critical_depth() {
+        let mut ctrl = MockController::enabled();
+        assert_eq!(ctrl.critical_depth(), 0);
+        {
+            let mut guard = ctrl.enter_critical();
+            assert_eq!(guard.critical_depth(), 1);
+            {
+                let guard = guard.enter_critical();
+                assert_eq!(guard.critical_depth(), 2);
+            }
+            assert_eq!(guard.critical_depth(), 1);
+        }
+        assert_eq!(ctrl.critical_depth(), 0);
+    }
+
+    #[test]
+    fn timer_tick_cx_default() {
+        struct MockContext(MockRegisters);
+
+        impl ctx::Context for MockContext {
+            type Registers = MockRegisters;
+
+            fn registers(&self) -> &Self::Registers {
+                &self.0
+            }
+
+            unsafe fn registers_mut(&mut self) -> &mut Self::Registers {
+                &mut self.0
+            }
+
+            fn instruction_pointer(&self) -> usize {
+                0
+            }
+
+            fn stack_pointer(&self) -> usize {
+                0
+            }
+        }
+
+        let ticks = TICKS.load(Ordering::Relaxed);
+        let action = MockHandlers::timer_tick_cx(MockContext(MockRegisters));
+        assert_eq!(action, TickAction::Continue);
+        // other tests may tick the timer concurrently.
+        assert!(TICKS.load(Ordering::Relaxed) > ticks);
+    }
+
+    /// A mock interrupt controller which can mask individual vectors.
+    #[derive(Debug, Default)]
+    struct MockMaskControl {
+        masked: u64,
+    }
+
+    impl Control for MockMaskControl {
+        type Registers = MockRegisters;
+
+        unsafe fn disable(&mut self) {
+            unreachable!("masking a vector must not disable all interrupts")
+        }
+
+        unsafe fn enable(&mut self) {
+            unreachable!("unmasking a vector must not enable all interrupts")
+        }
+
+        fn is_enabled(&self) -> bool {
+            true
+        }
+
+        fn mask(&mut self, vector: u8) -> bool {
+            assert_eq!(
+                self.masked & (1 << vector),
+                0,
+                "vector {vector} masked twice"
+            );
+            self.masked |= 1 << vector;
+            true
+        }
+
+        fn unmask(&mut self, vector: u8) {
+            assert_ne!(self.masked & (1 << vector), 0, "vector {vector} not masked");
+            self.masked &= !(1 << vector);
+        }
+
+        fn register_handlers<H>(&mut self) -> Result<(), RegistrationError>
+        where
+            H: Handlers<Self::Registers>,
+        {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn mask_scoped() {
+        let mut ctrl = MockMaskControl::default();
+        {
+            let mut guard = ctrl.mask_scoped(3);
+            assert_eq!(guard.vector(), 3);
+            assert_eq!(guard.masked, 1 << 3);
+            {
+                let guard = guard.mask_scoped(5);
+                assert_eq!(guard.masked, 1 << 3 | 1 << 5);
+            }
+            assert_eq!(guard.masked, 1 << 3);
+        }
+        assert_eq!(ctrl.masked, 0);
+    }
+
+    #[test]
+    fn mask_default_critical_section() {
+        let mut ctrl = MockController::enabled();
+        assert!(!ctrl.mask(3));
+        assert!(ctrl.is_enabled());
+        {
+            let guard = ctrl.mask_scoped(3);
+            assert!(!guard.is_enabled());
+            assert_eq!(guard.critical_depth(), 1);
+        }
+        assert!(ctrl.is_enabled());
+        assert_eq!(ctrl.critical_depth(), 0);
+
+        {
+            let mut guard = ctrl.enter_critical();
+            {
+                let guard = guard.mask_scoped(3);
+                assert!(!guard.is_enabled());
+            }
+            // Unmasking inside a critical section must not re-enable
+            // interrupts.
+            assert!(!guard.is_enabled());
+        }
+        assert!(ctrl.is_enabled());
+        assert_eq!(ctrl.disable_count(), 2);
+        assert_eq!(ctrl.enable_count(), 2);
+    }
+
+    /// A mock interrupt controller which cannot mask individual vectors, and
+    /// does not track the critical section depth.
+    #[derive(Debug)]
+    struct MockNoDepthControl {
+        enabled: bool,
+    }
+
+    impl Control for MockNoDepthControl {
+        type Registers = MockRegisters;
+
+        unsafe fn disable(&mut self) {
+            assert!(self.enabled, "interrupts disabled while already disabled");
+            self.enabled = false;
+        }
+
+        unsafe fn enable(&mut self) {
+            assert!(!self.enabled, "interrupts enabled while already enabled");
+            self.enabled = true;
+        }
+
+        fn is_enabled(&self) -> bool {
+            self.enabled
+        }
+
+        fn register_handlers<H>(&mut self) -> Result<(), RegistrationError>
+        where
+            H: Handlers<Self::Registers>,
+        {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn mask_default_without_depth() {
+        let mut ctrl = MockNoDepthControl { enabled: true };
+        {
+            let mut guard = ctrl.enter_critical();
+            {
+                let guard = guard.mask_scoped(3);
+                assert!(!guard.is_enabled());
+            }
+            // The controller does not track the critical section depth, but
+            // unmasking must still not re-enable interrupts inside the
+            // critical section.
+            assert!(!guard.is_enabled());
+        }
+        assert!(ctrl.is_enabled());
+    }
+
+    #[test]
+    fn mask_default_in_priority_section() {
+        let mut ctrl = MockController::enabled();
+        {
+            let mut guard = ctrl.enter_critical_at(PriorityLevel::new(4));
+            {
+                let guard = guard.mask_scoped(3);
+                assert!(!guard.is_enabled());
+            }
+            // Interrupts were enabled when the vector was masked, so they
+            // must be re-enabled when it is unmasked.
+            assert!(guard.is_enabled());
+        }
+        assert!(ctrl.is_enabled());
+        assert_eq!(ctrl.critical_depth(), 0);
+    }
+}
@@ -5,21 +5,107 @@ pub trait Context {
     // TODO(eliza): Registers trait
     type Registers: fmt::Debug + fmt::Display;
 
+    /// Returns the registers saved when the interrupt occurred.
     fn registers(&self) -> &Self::Registers;
 
+    /// Returns a mutable reference to the registers saved when the interrupt
+    /// occurred.
+    ///
+    /// When the interrupt handler returns, the interrupted code resumes with
+    /// the saved register state. Modifying the saved registers therefore
+    /// changes how the interrupted code resumes: for example, a preemptive
+    /// scheduler may replace them with the saved state of a different task,
+    /// or a page fault handler may advance the instruction pointer past a
+    /// faulting instruction that it has emulated.
+    ///
+    /// Whether modifications are restored when the interrupt returns is
+    /// platform-specific; platforms which cannot restore modified registers
+    /// must document this.
+    ///
     /// # Safety
     ///
-    /// Mutating the value of saved interrupt registers can cause
-    /// undefined behavior.
+    /// Mutating the value of saved interrupt registers can cause undefined
+    /// behavior. Callers must ensure that:
+    ///
+    /// - the instruction pointer refers to valid, executable code for the
+    ///   privilege level the interrupted code resumes at,
+    /// - the stack pointer refers to a valid stack that is not in use by any
+    ///   other context,
+    /// - any segment selectors and flags registers (such as x86's
+    ///   `code_segment`, `stack_segment`, and `cpu_flags`) remain valid, and
+    ///   do not grant the interrupted code a higher privilege level than it
+    ///   previously had, unless that is the intent, and
+    /// - the resulting register state is one the interrupted code (or the
+    ///   task being switched to) expects to resume with.
+    ///
+    /// Reading through the returned reference, and writing back values read
+    /// from the same registers, is always safe.
     unsafe fn registers_mut(&mut self) -> &mut Self::Registers;
+
+    /// Returns the value of the instruction pointer in the interrupted
+    /// context.
+    ///
+    /// This is the address of the instruction that was executing (or about
+    /// to execute) when the interrupt occurred, *not* the address of the
+    /// interrupt handler.
+    fn instruction_pointer(&self) -> usize;
+
+    /// Returns the value of the stack pointer in the interrupted context.
+    ///
+    /// This is the top of the stack that was in use when the interrupt
+    /// occurred, *not* the interrupt handler's stack.
+    fn stack_pointer(&self) -> usize;
 }
 
+/// Trait representing a page fault.
 pub trait PageFault: Context {
+    /// Returns the virtual address whose access caused the page fault.
+    ///
+    /// # Platform Responsibilities
+    ///
+    /// Platform implementations must return the address that the faulting
+    /// instruction attempted to access, *not* the address of the faulting
+    /// instruction itself (which is available from the saved registers).
+    /// For example, on x86_64, this is the value of the `cr2` control
+    /// register.
+    ///
+    /// If the platform only records the faulting address in a register that
+    /// may be overwritten by a subsequent fault, the implementation must
+    /// ensure that the returned address belongs to *this* fault, such as by
+    /// reading it before any code that could fault again runs.
     fn fault_vaddr(&self) -> VAddr;
+
+    /// Returns a [`PageFaultCode`] describing the cause of the page fault.
+    fn error_code(&self) -> PageFaultCode;
+
+    /// Returns the platform-specific page fault error code, for debugging.
     fn debug_error_code(&self) -> &dyn fmt::Debug;
     // TODO(eliza): more
 }
 
+mycelium_util::bits::bitfield! {
+    /// Describes the cause of a page fault.
+    ///
+    /// The bit layout of this type matches the error code pushed by x86 CPUs
+    /// when a page fault occurs. Platforms with a different error code format
+    /// should translate their error codes into this representation.
+    pub struct PageFaultCode<u32> {
+        /// If set, the fault was caused by a page-protection violation. If
+        /// unset, the fault was caused by a non-present page.
+        pub const PRESENT: bool;
+        /// If set, the fault was caused by a write access. If unset, the
+        /// fault was caused by a read access.
+        pub const WRITE: bool;
+        /// If set, the fault occurred while executing in user mode.
+        pub const USER: bool;
+        /// If set, the fault was caused by a reserved bit being set in a page
+        /// table entry.
+        pub const RESERVED_WRITE: bool;
+        /// If set, the fault was caused by an instruction fetch.
+        pub const INSTRUCTION_FETCH: bool;
+    }
+}
+
 /// Trait representing a fault caused by the currently executing code.
 pub trait CodeFault: Context {
     /// Returns `true` if the code fault occurred while executing in user
@@ -33,6 +119,12 @@ pub trait CodeFault: Context {
     /// Returns a static string describing the kind of code fault.
     fn fault_kind(&self) -> &'static str;
 
+    /// Returns a [`CodeFaultKind`] describing the kind of code fault.
+    ///
+    /// Unlike [`fault_kind`](Self::fault_kind), this may be matched on to
+    /// handle specific faults.
+    fn kind(&self) -> CodeFaultKind;
+
     /// Returns a dynamically formatted message if additional information about
     /// the fault is available. Otherwise, this returns `None`.
     ///
@@ -44,13 +136,60 @@ pub trait CodeFault: Context {
     }
 }
 
+/// The kind of a [`CodeFault`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 #[non_exhaustive]
 pub enum CodeFaultKind {
     /// The code fault was a division by zero.
     Division,
     /// The code fault was caused by an invalid instruction.
     InvalidInstruction,
+    /// The code fault was caused by an arithmetic overflow check.
     Overflow,
+    /// The code fault was caused by an array bounds check.
+    BoundRange,
+    /// The code fault was caused by an unaligned memory access.
     Alignment,
-    Other(&'static str),
+    /// The code fault was caused by using a floating-point or SIMD unit that
+    /// is not available.
+    DeviceNotAvailable,
+    /// The code fault was a floating-point exception.
+    FloatingPoint,
+    /// The code fault was a SIMD floating-point exception.
+    SimdFloatingPoint,
+    /// The code fault was caused by an invalid task-state segment.
+    InvalidTss,
+    /// The code fault was caused by referencing a segment which is not
+    /// present.
+    SegmentNotPresent,
+    /// The code fault was caused by a stack segment fault.
+    StackSegment,
+    /// The code fault was a general protection fault.
+    GeneralProtection,
+    /// Some other kind of code fault, with the interrupt vector on which it
+    /// occurred.
+    Other(u8),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_fault_code_decode() {
+        // a user-mode write to a present, read-only page.
+        let code = PageFaultCode::from_bits(0b00111);
+        assert!(code.get(PageFaultCode::PRESENT));
+        assert!(code.get(PageFaultCode::WRITE));
+        assert!(code.get(PageFaultCode::USER));
+        assert!(!code.get(PageFaultCode::RESERVED_WRITE));
+        assert!(!code.get(PageFaultCode::INSTRUCTION_FETCH));
+
+        // a kernel-mode instruction fetch from a non-present page.
+        let code = PageFaultCode::from_bits(0b10000);
+        assert!(!code.get(PageFaultCode::PRESENT));
+        assert!(!code.get(PageFaultCode::WRITE));
+        assert!(!code.get(PageFaultCode::USER));
+        assert!(code.get(PageFaultCode::INSTRUCTION_FETCH));
+    }
 }
@@ -0,0 +1,163 @@
+//! A mock interrupt controller for testing.
+//!
+//! This module is available in `hal-core`'s own tests, and to other crates
+//! when the `mock` feature flag is enabled. It provides [`MockController`], a
+//! [`Control`] implementation that performs no hardware operations, but
+//! records how it was used, so that code generic over [`Control`] (such as
+//! [`CriticalGuard`](super::CriticalGuard)) can be tested on the host.
+use super::{Control, HandlerSet, Handlers, RegistrationError};
+use core::fmt;
+
+/// A mock interrupt controller which records how it is used.
+///
+/// A `MockController` tracks whether interrupts are enabled and the current
+/// critical section depth, and counts calls to
+/// [`Control::disable`], [`Control::enable`], and
+/// [`Control::register_handlers`]. It does not support priority-based
+/// masking, so it uses the default implementations of
+/// [`Control::set_priority_mask`] and [`Control::priority_mask`].
+///
+/// By default, a `MockController` supports every [`Handlers`] method. A
+/// controller for a platform which cannot route interrupts to some handlers
+/// can be constructed using
+/// [`MockController::with_unsupported_handlers`].
+///
+/// # Panics
+///
+/// Like real hardware may fault in these cases, a `MockController` panics if
+/// interrupts are disabled while already disabled, or enabled while already
+/// enabled. This allows tests to assert that the code under test never does
+/// so.
+#[derive(Debug, Default)]
+pub struct MockController {
+    enabled: bool,
+    depth: usize,
+    max_depth: usize,
+    disables: usize,
+    enables: usize,
+    registrations: usize,
+    last_handlers: Option<&'static str>,
+    unsupported: HandlerSet,
+}
+
+/// The register type for [`MockController`].
+///
+/// This contains no registers; it exists so that tests may construct
+/// interrupt contexts for a [`MockController`].
+#[derive(Debug, Default)]
+pub struct MockRegisters;
+
+// === impl MockController ===
+
+impl MockController {
+    /// Returns a new `MockController` with interrupts disabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a new `MockController` with interrupts enabled.
+    pub fn enabled() -> Self {
+        Self {
+            enabled: true,
+            ..Self::default()
+        }
+    }
+
+    /// Returns this controller, configured so that
+    /// [`Control::register_handlers`] cannot register the [`Handlers`]
+    /// methods in `handlers`.
+    ///
+    /// When handlers are registered, the remaining handlers are registered
+    /// as usual, and a [`RegistrationError::nonexistant`] error listing
+    /// `handlers` is returned.
+    pub fn with_unsupported_handlers(self, handlers: HandlerSet) -> Self {
+        Self {
+            unsupported: handlers,
+            ..self
+        }
+    }
+
+    /// Returns the number of times interrupts have been disabled.
+    pub fn disable_count(&self) -> usize {
+        self.disables
+    }
+
+    /// Returns the number of times interrupts have been enabled.
+    ///
+    /// This does not include the initial state of a controller returned by
+    /// [`MockController::enabled`].
+    pub fn enable_count(&self) -> usize {
+        self.enables
+    }
+
+    /// Returns the number of times [`Control::register_handlers`] has been
+    /// called.
+    pub fn register_handlers_count(&self) -> usize {
+        self.registrations
+    }
+
+    /// Returns the type name of the [`Handlers`] implementation passed to the
+    /// most recent call to [`Control::register_handlers`], or `None` if no
+    /// handlers have been registered.
+    pub fn last_handlers(&self) -> Option<&'static str> {
+        self.last_handlers
+    }
+
+    /// Returns the greatest critical section depth this controller has
+    /// reached.
+    pub fn max_critical_depth(&self) -> usize {
+        self.max_depth
+    }
+}
+
+impl Control for MockController {
+    type Registers = MockRegisters;
+
+    unsafe fn disable(&mut self) {
+        assert!(self.enabled, "interrupts disabled while already disabled");
+        self.enabled = false;
+        self.disables += 1;
+    }
+
+    unsafe fn enable(&mut self) {
+        assert!(!self.enabled, "interrupts enabled while already enabled");
+        self.enabled = true;
+        self.enables += 1;
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn critical_depth(&self) -> usize {
+        self.depth
+    }
+
+    fn set_critical_depth(&mut self, depth: usize) {
+        self.depth = depth;
+        if depth > self.max_depth {
+            self.max_depth = depth;
+        }
+    }
+
+    fn register_handlers<H>(&mut self) -> Result<(), RegistrationError>
+    where
+        H: Handlers<Self::Registers>,
+    {
+        H::init();
+        self.registrations += 1;
+        self.last_handlers = Some(core::any::type_name::<H>());
+        if !self.unsupported.is_empty() {
+            return Err(RegistrationError::nonexistant().with_handlers(self.unsupported));
+        }
+        Ok(())
+    }
+}
+
+// === impl MockRegisters ===
+
+impl fmt::Display for MockRegisters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MockRegisters")
+    }
+}
@@ -1,3 +1,23 @@
+pub mod cr2 {
+    use crate::VAddr;
+    use core::arch::asm;
+    use hal_core::Address;
+
+    /// Returns the page fault linear address (PFLA) stored in `cr2`.
+    ///
+    /// When a page fault occurs, the CPU stores the virtual address whose
+    /// access caused the fault in `cr2`. This value is only meaningful while
+    /// handling a page fault, and will be overwritten if another page fault
+    /// occurs.
+    pub fn read() -> VAddr {
+        let val: u64;
+        unsafe {
+            asm!("mov {0}, cr2", out(reg) val, options(nomem, nostack, preserves_flags));
+        };
+        VAddr::from_u64(val)
+    }
+}
+
 pub mod cr3 {
     use crate::{mm::size::Size4Kb, PAddr};
     use core::arch::asm;
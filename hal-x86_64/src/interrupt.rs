@@ -1,6 +1,14 @@
 use crate::{segment, VAddr};
-use core::{arch::asm, fmt, marker::PhantomData};
-use hal_core::interrupt::{ctx, Handlers};
+use core::{
+    arch::asm,
+    fmt,
+    marker::PhantomData,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use hal_core::{
+    interrupt::{ctx, Handlers},
+    Address,
+};
 
 pub mod idt;
 pub mod pic;
@@ -19,6 +27,7 @@ pub struct Context<'a, T = ()> {
 pub type ErrorCode = u64;
 
 pub struct CodeFault<'a> {
+    vector: u8,
     kind: &'static str,
     error_code: Option<&'a dyn fmt::Display>,
 }
@@ -64,6 +73,13 @@ pub struct Registers {
 static mut IDT: idt::Idt = idt::Idt::new();
 static mut PIC: pic::CascadedPic = pic::CascadedPic::new();
 
+/// The number of critical sections currently entered through the [`Idt`].
+///
+/// This is a global rather than a field of `Idt`, since the `Idt` itself is
+/// loaded by the CPU and must have the layout the hardware expects.
+// TODO: this should be per-CPU once we support SMP.
+static CRITICAL_DEPTH: AtomicUsize = AtomicUsize::new(0);
+
 pub fn init<H: Handlers<Registers>>() -> Control {
     use hal_core::interrupt::Control;
 
@@ -100,15 +116,31 @@ impl<'a, T> hal_core::interrupt::Context for Context<'a, T> {
     /// # Safety
     ///
     /// Mutating the value of saved interrupt registers can cause
-    /// undefined behavior.
+    /// undefined behavior. See [`hal_core::interrupt::Context::registers_mut`]
+    /// for details.
+    ///
+    /// The saved registers are restored by the `iretq` instruction when the
+    /// interrupt handler returns, so modifications made here take effect.
     unsafe fn registers_mut(&mut self) -> &mut Registers {
         self.registers
     }
+
+    fn instruction_pointer(&self) -> usize {
+        self.registers.instruction_ptr.as_usize()
+    }
+
+    fn stack_pointer(&self) -> usize {
+        self.registers.stack_ptr.as_usize()
+    }
 }
 
 impl<'a> ctx::PageFault for Context<'a, PageFaultCode> {
     fn fault_vaddr(&self) -> crate::VAddr {
-        unimplemented!("eliza")
+        crate::control_regs::cr2::read()
+    }
+
+    fn error_code(&self) -> ctx::PageFaultCode {
+        ctx::PageFaultCode::from_bits(self.code.0)
     }
 
     fn debug_error_code(&self) -> &dyn fmt::Debug {
@@ -129,6 +161,10 @@ impl<'a> ctx::CodeFault for Context<'a, CodeFault<'a>> {
         self.code.kind
     }
 
+    fn kind(&self) -> ctx::CodeFaultKind {
+        Idt::code_fault_kind(self.code.vector)
+    }
+
     fn details(&self) -> Option<&dyn fmt::Display> {
         self.code.error_code
     }
@@ -161,7 +197,21 @@ impl hal_core::interrupt::Control for Idt {
     }
 
     fn is_enabled(&self) -> bool {
-        unimplemented!("eliza do this one!!!")
+        // The interrupt enable flag is bit 9 of `%rflags`.
+        const INTERRUPT_FLAG: u64 = 1 << 9;
+        let rflags: u64;
+        unsafe {
+            asm!("pushfq", "pop {}", out(reg) rflags, options(preserves_flags));
+        }
+        rflags & INTERRUPT_FLAG != 0
+    }
+
+    fn critical_depth(&self) -> usize {
+        CRITICAL_DEPTH.load(Ordering::Relaxed)
+    }
+
+    fn set_critical_depth(&mut self, depth: usize) {
+        CRITICAL_DEPTH.store(depth, Ordering::Relaxed);
     }
 
     fn register_handlers<H>(&mut self) -> Result<(), hal_core::interrupt::RegistrationError>
@@ -171,25 +221,27 @@ impl hal_core::interrupt::Control for Idt {
         macro_rules! gen_code_faults {
             ($self:ident, $h:ty, $($vector:path => fn $name:ident($($rest:tt)+),)+) => {
                 $(
-                    gen_code_faults! {@ $name($($rest)+); }
+                    gen_code_faults! {@ $name($vector, $($rest)+); }
                     $self.set_isr($vector, $name::<$h> as *const ());
                 )+
             };
-            (@ $name:ident($kind:literal);) => {
+            (@ $name:ident($vector:path, $kind:literal);) => {
                 extern "x86-interrupt" fn $name<H: Handlers<Registers>>(mut registers: Registers) {
                     let code = CodeFault {
+                        vector: $vector as u8,
                         error_code: None,
                         kind: $kind,
                     };
                     H::code_fault(Context { registers: &mut registers, code });
                 }
             };
-            (@ $name:ident($kind:literal, code);) => {
+            (@ $name:ident($vector:path, $kind:literal, code);) => {
                 extern "x86-interrupt" fn $name<H: Handlers<Registers>>(
                     mut registers: Registers,
                     code: u64,
                 ) {
                     let code = CodeFault {
+                        vector: $vector as u8,
                         error_code: Some(&code),
                         kind: $kind,
                     };
@@ -201,6 +253,8 @@ impl hal_core::interrupt::Control for Idt {
         let span = tracing::debug_span!("Idt::register_handlers");
         let _enter = span.enter();
 
+        H::init();
+
         extern "x86-interrupt" fn page_fault_isr<H: Handlers<Registers>>(
             mut registers: Registers,
             code: PageFaultCode,
@@ -221,15 +275,26 @@ impl hal_core::interrupt::Control for Idt {
             });
         }
 
-        extern "x86-interrupt" fn timer_isr<H: Handlers<Registers>>(_regs: Registers) {
-            H::timer_tick();
+        extern "x86-interrupt" fn timer_isr<H: Handlers<Registers>>(mut registers: Registers) {
+            // TODO: switch tasks when the handler asks for a reschedule. We
+            // can't switch tasks from an interrupt yet, so
+            // `TickAction::Reschedule` is treated the same as `Continue`.
+            let _action = H::timer_tick_cx(Context {
+                registers: &mut registers,
+                code: (),
+            });
             unsafe {
                 PIC.end_interrupt(0x20);
             }
         }
 
         extern "x86-interrupt" fn keyboard_isr<H: Handlers<Registers>>(_regs: Registers) {
-            H::keyboard_controller();
+            // load-bearing read - if we don't read from the keyboard controller it won't
+            // send another interrupt on later keystrokes.
+            //
+            // 0x60 is a magic PC/AT number.
+            let scancode = unsafe { crate::cpu::Port::at(0x60).readb() };
+            H::keyboard_controller(scancode);
             unsafe {
                 PIC.end_interrupt(0x21);
             }
@@ -259,6 +324,7 @@ impl hal_core::interrupt::Control for Idt {
             let msg = selector.named("task-state segment (TSS)");
             let code = CodeFault {
                 error_code: Some(&msg),
+                vector: Idt::INVALID_TSS as u8,
                 kind: "Invalid TSS (0xA)",
             };
             H::code_fault(Context {
@@ -284,6 +350,7 @@ impl hal_core::interrupt::Control for Idt {
             let msg = selector.named("stack segment");
             let code = CodeFault {
                 error_code: Some(&msg),
+                vector: Idt::SEGMENT_NOT_PRESENT as u8,
                 kind: "Segment Not Present (0xB)",
             };
             H::code_fault(Context {
@@ -309,6 +376,7 @@ impl hal_core::interrupt::Control for Idt {
             let msg = selector.named("stack segment");
             let code = CodeFault {
                 error_code: Some(&msg),
+                vector: Idt::STACK_SEGMENT_FAULT as u8,
                 kind: "Stack-Segment Fault (0xC)",
             };
             H::code_fault(Context {
@@ -340,6 +408,7 @@ impl hal_core::interrupt::Control for Idt {
             let error_code = segment.map(|seg| seg.named("selector"));
             let code = CodeFault {
                 error_code: error_code.as_ref().map(|code| code as &dyn fmt::Display),
+                vector: Idt::GENERAL_PROTECTION_FAULT as u8,
                 kind: "General Protection Fault (0xD)",
             };
             H::code_fault(Context {
@@ -350,14 +419,14 @@ impl hal_core::interrupt::Control for Idt {
 
         gen_code_faults! {
             self, H,
-            Self::DIVIDE_BY_ZERO => fn div_0_isr("Divide-By-Zero (0x0)"),
-            Self::OVERFLOW => fn overflow_isr("Overflow (0x4)"),
-            Self::BOUND_RANGE_EXCEEDED => fn br_isr("Bound Range Exceeded (0x5)"),
-            Self::INVALID_OPCODE => fn ud_isr("Invalid Opcode (0x6)"),
-            Self::DEVICE_NOT_AVAILABLE => fn no_fpu_isr("Device (FPU) Not Available (0x7)"),
-            Self::ALIGNMENT_CHECK => fn alignment_check_isr("Alignment Check (0x11)", code),
-            Self::SIMD_FLOATING_POINT => fn simd_fp_exn_isr("SIMD Floating-Point Exception (0x13)"),
-            Self::X87_FPU_EXCEPTION => fn x87_exn_isr("x87 Floating-Point Exception (0x10)"),
+            Idt::DIVIDE_BY_ZERO => fn div_0_isr("Divide-By-Zero (0x0)"),
+            Idt::OVERFLOW => fn overflow_isr("Overflow (0x4)"),
+            Idt::BOUND_RANGE_EXCEEDED => fn br_isr("Bound Range Exceeded (0x5)"),
+            Idt::INVALID_OPCODE => fn ud_isr("Invalid Opcode (0x6)"),
+            Idt::DEVICE_NOT_AVAILABLE => fn no_fpu_isr("Device (FPU) Not Available (0x7)"),
+            Idt::ALIGNMENT_CHECK => fn alignment_check_isr("Alignment Check (0x11)", code),
+            Idt::SIMD_FLOATING_POINT => fn simd_fp_exn_isr("SIMD Floating-Point Exception (0x13)"),
+            Idt::X87_FPU_EXCEPTION => fn x87_exn_isr("x87 Floating-Point Exception (0x10)"),
         }
 
         self.set_isr(0x20, timer_isr::<H> as *const ());
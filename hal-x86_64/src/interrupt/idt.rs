@@ -1,5 +1,6 @@
 use crate::{cpu, segment};
 use core::{arch::asm, fmt};
+use hal_core::interrupt::ctx::CodeFaultKind;
 use mycelium_util::bits;
 
 #[repr(C)]
@@ -136,6 +137,27 @@ impl Idt {
 
     pub const SECURITY_EXCEPTION: usize = 30;
 
+    /// Returns the [`CodeFaultKind`] of the exception on `vector`.
+    ///
+    /// Vectors which are not code faults return [`CodeFaultKind::Other`].
+    pub const fn code_fault_kind(vector: u8) -> CodeFaultKind {
+        match vector as usize {
+            Self::DIVIDE_BY_ZERO => CodeFaultKind::Division,
+            Self::OVERFLOW => CodeFaultKind::Overflow,
+            Self::BOUND_RANGE_EXCEEDED => CodeFaultKind::BoundRange,
+            Self::INVALID_OPCODE => CodeFaultKind::InvalidInstruction,
+            Self::DEVICE_NOT_AVAILABLE => CodeFaultKind::DeviceNotAvailable,
+            Self::INVALID_TSS => CodeFaultKind::InvalidTss,
+            Self::SEGMENT_NOT_PRESENT => CodeFaultKind::SegmentNotPresent,
+            Self::STACK_SEGMENT_FAULT => CodeFaultKind::StackSegment,
+            Self::GENERAL_PROTECTION_FAULT => CodeFaultKind::GeneralProtection,
+            Self::X87_FPU_EXCEPTION => CodeFaultKind::FloatingPoint,
+            Self::ALIGNMENT_CHECK => CodeFaultKind::Alignment,
+            Self::SIMD_FLOATING_POINT => CodeFaultKind::SimdFloatingPoint,
+            _ => CodeFaultKind::Other(vector),
+        }
+    }
+
     pub const fn new() -> Self {
         Self {
             descriptors: [Descriptor::null(); Self::NUM_VECTORS],
@@ -307,4 +329,31 @@ mod tests {
 
         assert_eq!(idt_bytes, &expected_idt, "\n entry: {:#?}", idt_entry);
     }
+
+    #[test]
+    fn code_fault_kinds() {
+        let kinds = [
+            (Idt::DIVIDE_BY_ZERO, CodeFaultKind::Division),
+            (Idt::OVERFLOW, CodeFaultKind::Overflow),
+            (Idt::BOUND_RANGE_EXCEEDED, CodeFaultKind::BoundRange),
+            (Idt::INVALID_OPCODE, CodeFaultKind::InvalidInstruction),
+            (Idt::DEVICE_NOT_AVAILABLE, CodeFaultKind::DeviceNotAvailable),
+            (Idt::INVALID_TSS, CodeFaultKind::InvalidTss),
+            (Idt::SEGMENT_NOT_PRESENT, CodeFaultKind::SegmentNotPresent),
+            (Idt::STACK_SEGMENT_FAULT, CodeFaultKind::StackSegment),
+            (
+                Idt::GENERAL_PROTECTION_FAULT,
+                CodeFaultKind::GeneralProtection,
+            ),
+            (Idt::X87_FPU_EXCEPTION, CodeFaultKind::FloatingPoint),
+            (Idt::ALIGNMENT_CHECK, CodeFaultKind::Alignment),
+            (Idt::SIMD_FLOATING_POINT, CodeFaultKind::SimdFloatingPoint),
+            (Idt::NMI, CodeFaultKind::Other(2)),
+            (Idt::PAGE_FAULT, CodeFaultKind::Other(14)),
+            (0x20, CodeFaultKind::Other(0x20)),
+        ];
+        for (vector, kind) in kinds {
+            assert_eq!(Idt::code_fault_kind(vector as u8), kind, "vector {vector}");
+        }
+    }
 }
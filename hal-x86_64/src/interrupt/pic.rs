@@ -19,6 +19,10 @@ impl Pic {
 
 pub struct CascadedPic {
     sisters: PicSisters,
+    /// The interrupt mask registers of the primary and secondary PICs,
+    /// saved by `disable` so that `enable` can restore them. This is `Some`
+    /// while interrupts are disabled.
+    saved_masks: Option<[u8; 2]>,
 }
 
 // two of them
@@ -43,6 +47,7 @@ impl CascadedPic {
     pub(crate) const fn new() -> Self {
         Self {
             sisters: PicSisters::new(),
+            saved_masks: None,
         }
     }
 
@@ -58,6 +63,33 @@ impl CascadedPic {
             self.sisters.big.command.writeb(END_INTERRUPT);
         }
     }
+
+    /// Sets or clears the mask bit for the IRQ line of the interrupt
+    /// `vector`, returning `false` if `vector` is not a PIC interrupt.
+    ///
+    /// While interrupts are disabled, the saved interrupt mask is updated
+    /// instead, so that the change takes effect when they are re-enabled.
+    fn set_masked(&mut self, vector: u8, masked: bool) -> bool {
+        let mut pics = [&mut self.sisters.big, &mut self.sisters.little];
+        for (i, pic) in pics.iter_mut().enumerate() {
+            if vector < pic.address || vector >= pic.address + 8 {
+                continue;
+            }
+            // a set bit in a PIC's data register masks the corresponding IRQ
+            // line.
+            let bit = 1 << (vector - pic.address);
+            let update = |mask: u8| if masked { mask | bit } else { mask & !bit };
+            match self.saved_masks {
+                Some(ref mut saved) => saved[i] = update(saved[i]),
+                None => unsafe {
+                    let mask = pic.data.readb();
+                    pic.data.writeb(update(mask));
+                },
+            }
+            return true;
+        }
+        false
+    }
 }
 
 impl hal_core::interrupt::Control for CascadedPic {
@@ -72,6 +104,14 @@ impl hal_core::interrupt::Control for CascadedPic {
     }
 
     unsafe fn disable(&mut self) {
+        // save the current masks, so that lines masked individually by
+        // `mask` stay masked when interrupts are re-enabled.
+        if self.saved_masks.is_none() {
+            self.saved_masks = Some([
+                self.sisters.big.data.readb(),
+                self.sisters.little.data.readb(),
+            ]);
+        }
         self.sisters.big.data.writeb(0xff);
         self.sisters.little.data.writeb(0xff);
     }
@@ -79,12 +119,27 @@ impl hal_core::interrupt::Control for CascadedPic {
     unsafe fn enable(&mut self) {
         // TODO(ixi): confirm this?? it looks like "disable" is "write a 1 to set the line masked"
         //            so maybe it stands to reason that writing a 0 unmasks an interrupt?
-        self.sisters.big.data.writeb(0x00);
-        self.sisters.little.data.writeb(0x00);
+        let [big, little] = self.saved_masks.take().unwrap_or([0x00, 0x00]);
+        self.sisters.big.data.writeb(big);
+        self.sisters.little.data.writeb(little);
+    }
+
+    fn end_of_interrupt(&mut self, vector: u8) {
+        self.end_interrupt(vector)
+    }
+
+    fn mask(&mut self, vector: u8) -> bool {
+        self.set_masked(vector, true)
+    }
+
+    fn unmask(&mut self, vector: u8) {
+        self.set_masked(vector, false);
     }
 
     fn is_enabled(&self) -> bool {
-        unimplemented!("ixi do this one!!!")
+        // `disable` saves the interrupt masks until interrupts are enabled
+        // again.
+        self.saved_masks.is_none()
     }
 }
 
@@ -1,6 +1,6 @@
 use bootloader::boot_info;
 use core::sync::atomic::{AtomicUsize, Ordering};
-use hal_core::{boot::BootInfo, mem, PAddr, VAddr};
+use hal_core::{boot::BootInfo, interrupt::TickAction, mem, PAddr, VAddr};
 use hal_x86_64::{cpu, interrupt::Registers as X64Registers, serial, vga};
 pub use hal_x86_64::{interrupt, mm, NAME};
 use mycelium_util::sync::InitOnce;
@@ -129,21 +129,45 @@ impl RustbootBootInfo {
 static TEST_INTERRUPT_WAS_FIRED: AtomicUsize = AtomicUsize::new(0);
 
 pub(crate) static TIMER: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of spurious interrupts received, for diagnostics.
+static SPURIOUS_INTERRUPTS: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of timer ticks after which the timer handler requests a
+/// reschedule.
+const TIMESLICE_TICKS: usize = 10;
 pub(crate) struct InterruptHandlers;
 
-/// Forcibly unlock the IOs we write to in an oops (VGA buffer and COM1 serial
-/// port) to prevent deadlocks if the oops occured while either was locked.
-///
-/// # Safety
-///
-///  /!\ only call this when oopsing!!! /!\
 impl hal_core::interrupt::Handlers<X64Registers> for InterruptHandlers {
     fn page_fault<C>(cx: C)
     where
         C: hal_core::interrupt::Context<Registers = X64Registers>
             + hal_core::interrupt::ctx::PageFault,
     {
-        oops(Oops::fault(&cx, "PAGE FAULT"))
+        use hal_core::interrupt::ctx::PageFaultCode;
+
+        let fault_vaddr = cx.fault_vaddr();
+        let code = cx.error_code();
+        let access = if code.get(PageFaultCode::INSTRUCTION_FETCH) {
+            "instruction fetch"
+        } else if code.get(PageFaultCode::WRITE) {
+            "write"
+        } else {
+            "read"
+        };
+        tracing::error!(
+            ?fault_vaddr,
+            access,
+            ?code,
+            instruction_ptr = %format_args!("{:#x}", cx.instruction_pointer()),
+            stack_ptr = %format_args!("{:#x}", cx.stack_pointer()),
+            "page fault",
+        );
+        oops(Oops::fault_with_details(
+            &cx,
+            "PAGE FAULT",
+            &format_args!("{} at {:?}\n{}", access, fault_vaddr, code),
+        ))
     }
 
     fn code_fault<C>(cx: C)
@@ -151,30 +175,94 @@ impl hal_core::interrupt::Handlers<X64Registers> for InterruptHandlers {
         C: hal_core::interrupt::Context<Registers = X64Registers>
             + hal_core::interrupt::ctx::CodeFault,
     {
+        use hal_core::interrupt::ctx::CodeFaultKind;
+
+        let kind = cx.kind();
+        tracing::error!(
+            ?kind,
+            instruction_ptr = %format_args!("{:#x}", cx.instruction_pointer()),
+            stack_ptr = %format_args!("{:#x}", cx.stack_pointer()),
+            "code fault",
+        );
+        let kind = match kind {
+            CodeFaultKind::Division => "DIVIDE ERROR",
+            CodeFaultKind::InvalidInstruction => "INVALID INSTRUCTION",
+            CodeFaultKind::Overflow => "OVERFLOW",
+            CodeFaultKind::BoundRange => "BOUND RANGE EXCEEDED",
+            CodeFaultKind::Alignment => "ALIGNMENT CHECK",
+            CodeFaultKind::DeviceNotAvailable => "DEVICE NOT AVAILABLE",
+            CodeFaultKind::FloatingPoint => "FLOATING-POINT EXCEPTION",
+            CodeFaultKind::SimdFloatingPoint => "SIMD FLOATING-POINT EXCEPTION",
+            CodeFaultKind::InvalidTss => "INVALID TSS",
+            CodeFaultKind::SegmentNotPresent => "SEGMENT NOT PRESENT",
+            CodeFaultKind::StackSegment => "STACK SEGMENT FAULT",
+            CodeFaultKind::GeneralProtection => "GENERAL PROTECTION FAULT",
+            // the vector was logged above.
+            _ => "CODE FAULT",
+        };
         let fault = match cx.details() {
-            Some(deets) => Oops::fault_with_details(&cx, cx.fault_kind(), deets),
-            None => Oops::fault(&cx, cx.fault_kind()),
+            Some(deets) => Oops::fault_with_details(&cx, kind, deets),
+            None => Oops::fault(&cx, kind),
         };
         oops(fault)
     }
 
+    /// Handles a double fault by logging the faulting context and halting.
+    ///
+    /// The kernel's state can no longer be trusted after a double fault, so
+    /// rather than drawing an oops screen, the CPU is halted with interrupts
+    /// disabled (see [`cpu::halt`]), and is never resumed.
     fn double_fault<C>(cx: C)
     where
         C: hal_core::interrupt::Context<Registers = X64Registers>,
     {
-        oops(Oops::fault(&cx, "DOUBLE FAULT"))
+        // The fault may have occurred while the serial port, VGA buffer, or
+        // framebuffer was locked, so forcibly unlock them before logging.
+        //
+        // Safety: we are about to halt, so the previous holders of these
+        // locks will never use them again.
+        unsafe {
+            cpu::intrinsics::cli();
+            oops::force_unlock_ios();
+        }
+        // The kernel's `tracing` subscriber writes events with blocking
+        // writes, so this has been written out by the time the CPU halts.
+        tracing::error!(
+            registers = ?cx.registers(),
+            instruction_ptr = %format_args!("{:#x}", cx.instruction_pointer()),
+            stack_ptr = %format_args!("{:#x}", cx.stack_pointer()),
+            "double fault; halting",
+        );
+        cpu::halt()
     }
 
-    fn timer_tick() {
-        TIMER.fetch_add(1, Ordering::Relaxed);
+    fn init() {
+        TIMER.store(0, Ordering::Relaxed);
     }
 
-    fn keyboard_controller() {
-        // load-bearing read - if we don't read from the keyboard controller it won't
-        // send another interrupt on later keystrokes.
+    fn timer_tick() -> TickAction {
+        let ticks = TIMER.fetch_add(1, Ordering::Relaxed) + 1;
+        if ticks % TIMESLICE_TICKS == 0 {
+            TickAction::Reschedule
+        } else {
+            TickAction::Continue
+        }
+    }
+
+    fn timer_tick_cx<C>(mut cx: C) -> TickAction
+    where
+        C: hal_core::interrupt::Context<Registers = X64Registers>,
+    {
+        // A preemptive scheduler would save the interrupted task's registers
+        // here, and replace them with those of the next task to run.
         //
-        // 0x60 is a magic PC/AT number.
-        let scancode = unsafe { hal_x86_64::cpu::Port::at(0x60).readb() };
+        // Safety: the registers are not modified, so the interrupted task
+        // resumes with the state it was preempted in.
+        let _registers = unsafe { cx.registers_mut() };
+        Self::timer_tick()
+    }
+
+    fn keyboard_controller(scancode: u8) {
         tracing::info!(
             // for now
             "got scancode {}. the time is now: {}",
@@ -183,6 +271,17 @@ impl hal_core::interrupt::Handlers<X64Registers> for InterruptHandlers {
         );
     }
 
+    fn device_interrupt(vector: u8) {
+        // TODO: once we have a driver model, look up the driver registered
+        // for this vector and dispatch to it.
+        tracing::debug!(vector, "device interrupt");
+    }
+
+    fn spurious_interrupt() {
+        let spurious = SPURIOUS_INTERRUPTS.fetch_add(1, Ordering::Relaxed) + 1;
+        tracing::trace!(spurious, "spurious interrupt");
+    }
+
     fn test_interrupt<C>(cx: C)
     where
         C: hal_core::interrupt::ctx::Context<Registers = X64Registers>,
@@ -192,6 +291,11 @@ impl hal_core::interrupt::Handlers<X64Registers> for InterruptHandlers {
     }
 }
 
+// The x86_64 keyboard ISR reads the scancode from the PS/2 controller and
+// passes it to the handler, so make sure the kernel's handler takes it.
+const _: fn(u8) =
+    <InterruptHandlers as hal_core::interrupt::Handlers<X64Registers>>::keyboard_controller;
+
 #[cfg(target_os = "none")]
 bootloader::entry_point!(arch_entry);
 
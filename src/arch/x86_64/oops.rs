@@ -35,10 +35,28 @@ enum OopsSituation<'a> {
 
 type Fault<'a> = &'a dyn interrupt::ctx::Context<Registers = X64Registers>;
 
+/// Forcibly unlock the IOs we write to in an oops (VGA buffer, COM1 serial
+/// port, and framebuffer) to prevent deadlocks if the oops occured while any
+/// of them was locked.
+///
+/// # Safety
+///
+///  /!\ only call this when oopsing (or otherwise about to halt)!!! /!\
+pub(super) unsafe fn force_unlock_ios() {
+    // If the system has a COM1, unlock it.
+    if let Some(com1) = serial::com1() {
+        com1.force_unlock();
+    }
+
+    // unlock the VGA buffer.
+    vga::writer().force_unlock();
+
+    // unlock the frame buffer
+    framebuf::force_unlock();
+}
+
 #[cold]
 pub fn oops(oops: Oops<'_>) -> ! {
-    let mut vga = vga::writer();
-
     // /!\ disable all interrupts, unlock everything to prevent deadlock /!\
     //
     // Safety: it is okay to do this because we are oopsing and everything
@@ -47,16 +65,7 @@ pub fn oops(oops: Oops<'_>) -> ! {
         // disable all interrupts.
         cpu::intrinsics::cli();
 
-        // If the system has a COM1, unlock it.
-        if let Some(com1) = serial::com1() {
-            com1.force_unlock();
-        }
-
-        // unlock the VGA buffer.
-        vga.force_unlock();
-
-        // unlock the frame buffer
-        framebuf::force_unlock();
+        force_unlock_ios();
     }
 
     // emit a DEBUG event first. with the default tracing configuration, these